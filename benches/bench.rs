@@ -23,6 +23,7 @@ use genmesh::{
     LruIndexer,
     Indexer,
     Vertices,
+    TriangleVertices,
     Triangulate
 };
 
@@ -166,3 +167,37 @@ fn sphere_256x256_index_triangulate(bench: &mut Bencher) {
         }
     });
 }
+
+#[bench]
+fn sphere_128x128_vertices(bench: &mut Bencher) {
+    bench.iter(|| {
+        let sphere = SphereUV::new(128, 128);
+        for i in sphere.vertices() {
+            black_box(i);
+        }
+    });
+}
+
+fn triangle_list() -> Vec<Triangle<(f32, f32, f32)>> {
+    Plane::subdivide(256, 256).triangulate().collect()
+}
+
+#[bench]
+fn vertices_256x256_triangles(bench: &mut Bencher) {
+    let triangles = triangle_list();
+    bench.iter(|| {
+        for v in triangles.iter().map(|&t| t).vertices() {
+            black_box(v);
+        }
+    });
+}
+
+#[bench]
+fn triangle_vertices_256x256_triangles(bench: &mut Bencher) {
+    let triangles = triangle_list();
+    bench.iter(|| {
+        for v in triangles.iter().map(|&t| t).triangle_vertices() {
+            black_box(v);
+        }
+    });
+}
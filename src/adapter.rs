@@ -0,0 +1,442 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::collections::{RingBuf, HashMap};
+use std::hash::Hash;
+
+use poly::{MapVertex, IntoPolygon};
+use math::Vector3;
+use {Triangle, Quad, Polygon};
+use Polygon::{PolyTri, PolyQuad};
+
+/// Replace each polygon in a stream with any number of new polygons.
+/// This can be used to add procedural detail to a mesh, the function
+/// is given each polygon of the source and the polygons it returns
+/// are emitted in order before the next source polygon is read.
+pub trait FlatMapPolygons<SRC, P, Q> {
+    /// expand each polygon into the polygons returned by `f`
+    fn flat_map_polygons<'a>(self, f: |P|:'a -> Vec<Q>) -> FlatMapPolygonsIterator<'a, SRC, P, Q>;
+}
+
+impl<P, Q, T: Iterator<P>> FlatMapPolygons<T, P, Q> for T {
+    fn flat_map_polygons<'a>(self, f: |P|:'a -> Vec<Q>) -> FlatMapPolygonsIterator<'a, T, P, Q> {
+        FlatMapPolygonsIterator {
+            source: self,
+            buffer: RingBuf::new(),
+            f: f
+        }
+    }
+}
+
+/// an iterator that emits the polygons produced from each source polygon
+pub struct FlatMapPolygonsIterator<'a, SRC, P, Q> {
+    source: SRC,
+    buffer: RingBuf<Q>,
+    f: |P|:'a -> Vec<Q>
+}
+
+impl<'a, P, Q, SRC: Iterator<P>> Iterator<Q> for FlatMapPolygonsIterator<'a, SRC, P, Q> {
+    fn next(&mut self) -> Option<Q> {
+        loop {
+            match self.buffer.pop_front() {
+                Some(p) => return Some(p),
+                None => ()
+            }
+
+            match self.source.next() {
+                Some(p) => {
+                    for q in (self.f)(p).into_iter() {
+                        self.buffer.push_back(q);
+                    }
+                }
+                None => return None
+            }
+        }
+    }
+}
+
+/// The polygon level sibling of `MapToVertices`. The supplied function is
+/// given each whole polygon and can replace it with a new polygon, which
+/// may be a different kind or use a different vertex type, or return
+/// `None` to drop it from the stream. Useful for culling back faces or
+/// degenerate polygons without collecting the stream.
+pub trait MapToPolygons<P, Q> {
+    /// from a iterator of polygons, produces a iterator of the polygons
+    /// returned by `f`, skipping the polygons it returned `None` for
+    fn polygons<'a>(self, f: |P|:'a -> Option<Q>) -> MapToPolygonsIterator<'a, Self, P, Q>;
+}
+
+impl<P, Q, T: Iterator<P>> MapToPolygons<P, Q> for T {
+    fn polygons<'a>(self, f: |P|:'a -> Option<Q>) -> MapToPolygonsIterator<'a, T, P, Q> {
+        MapToPolygonsIterator {
+            source: self,
+            f: f
+        }
+    }
+}
+
+/// an iterator that maps and filters whole polygons
+pub struct MapToPolygonsIterator<'a, SRC, P, Q> {
+    source: SRC,
+    f: |P|:'a -> Option<Q>
+}
+
+impl<'a, P, Q, SRC: Iterator<P>> Iterator<Q> for MapToPolygonsIterator<'a, SRC, P, Q> {
+    fn next(&mut self) -> Option<Q> {
+        loop {
+            match self.source.next() {
+                Some(p) => match (self.f)(p) {
+                    Some(q) => return Some(q),
+                    None => ()
+                },
+                None => return None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let (_, upper) = self.source.size_hint();
+        (0, upper)
+    }
+}
+
+/// Pair every vertex with the index of the polygon it belongs to, the
+/// vertices of the first polygon are tagged with 0, the second with 1
+/// and so on. Useful for flat shading each face with its own id.
+pub trait FaceIndex<SRC> {
+    /// tag each vertex with the index of its polygon in the stream
+    fn face_index(self) -> FaceIndexIterator<SRC>;
+}
+
+impl<P, T: Iterator<P>> FaceIndex<T> for T {
+    fn face_index(self) -> FaceIndexIterator<T> {
+        FaceIndexIterator {
+            source: self,
+            idx: 0
+        }
+    }
+}
+
+/// an iterator that pairs each vertex with the index of its polygon
+pub struct FaceIndexIterator<SRC> {
+    source: SRC,
+    idx: uint
+}
+
+impl<T, P: MapVertex<T, (T, uint), Q>, Q, SRC: Iterator<P>> Iterator<Q> for FaceIndexIterator<SRC> {
+    fn next(&mut self) -> Option<Q> {
+        let idx = self.idx;
+        self.source.next().map(|p| {
+            self.idx += 1;
+            p.map_vertex(|v| (v, idx))
+        })
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.source.size_hint()
+    }
+}
+
+/// Pair every vertex with a value computed once for the polygon it
+/// belongs to, such as a flat color for each face. This saves calling
+/// the function again for every vertex as `map_vertex` would.
+pub trait WithAttribute<P, C> {
+    /// tag each vertex with the value `f` returns for its polygon
+    fn with_attribute<'a>(self, f: |&P|:'a -> C) -> WithAttributeIterator<'a, Self, P, C>;
+}
+
+impl<P, C, T: Iterator<P>> WithAttribute<P, C> for T {
+    fn with_attribute<'a>(self, f: |&P|:'a -> C) -> WithAttributeIterator<'a, T, P, C> {
+        WithAttributeIterator {
+            source: self,
+            f: f
+        }
+    }
+}
+
+/// an iterator that pairs each vertex with the value of its polygon
+pub struct WithAttributeIterator<'a, SRC, P, C> {
+    source: SRC,
+    f: |&P|:'a -> C
+}
+
+impl<'a, T, C: Clone, P: MapVertex<T, (T, C), Q>, Q, SRC: Iterator<P>> Iterator<Q>
+    for WithAttributeIterator<'a, SRC, P, C> {
+    fn next(&mut self) -> Option<Q> {
+        self.source.next().map(|p| {
+            let c = (self.f)(&p);
+            p.map_vertex(|v| (v, c.clone()))
+        })
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.source.size_hint()
+    }
+}
+
+/// Look at every polygon of a stream as it passes through, without
+/// changing it. This works like `Iterator::inspect` and can be placed
+/// anywhere in a chain of adapters to count or check polygons while
+/// debugging. The polygons are only borrowed so the vertices do not need
+/// to be `Clone`.
+pub trait InspectPolygons<P> {
+    /// call `f` with each polygon before passing it on
+    fn inspect_polygons<'a>(self, f: |&P|:'a) -> InspectPolygonsIterator<'a, Self, P>;
+}
+
+impl<P, T: Iterator<P>> InspectPolygons<P> for T {
+    fn inspect_polygons<'a>(self, f: |&P|:'a) -> InspectPolygonsIterator<'a, T, P> {
+        InspectPolygonsIterator {
+            source: self,
+            f: f
+        }
+    }
+}
+
+/// an iterator that shows each polygon to a closure as it passes
+pub struct InspectPolygonsIterator<'a, SRC, P> {
+    source: SRC,
+    f: |&P|:'a
+}
+
+impl<'a, P, SRC: Iterator<P>> Iterator<P> for InspectPolygonsIterator<'a, SRC, P> {
+    fn next(&mut self) -> Option<P> {
+        self.source.next().map(|p| {
+            (self.f)(&p);
+            p
+        })
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.source.size_hint()
+    }
+}
+
+/// Combine every polygon of a stream into a single value, such as a sum
+/// or a bound. This is `Iterator::fold` under a name that reads like the
+/// rest of the polygon adapters, see `MeasurePolygons` for some common
+/// uses.
+pub trait FoldPolygons<P> {
+    /// start from `init` and combine it with each polygon in turn
+    fn fold_polygons<B>(self, init: B, f: |B, P| -> B) -> B;
+}
+
+impl<P, T: Iterator<P>> FoldPolygons<P> for T {
+    fn fold_polygons<B>(self, init: B, f: |B, P| -> B) -> B {
+        let mut acc = init;
+        for p in self {
+            acc = f(acc, p);
+        }
+        acc
+    }
+}
+
+/// Group a stream of polygons into batches of up to `n` polygons. Every
+/// batch is full except for the last one, which holds whatever is left.
+/// Useful for limiting the size of each index buffer or draw call.
+pub trait Chunks<SRC> {
+    /// split the stream into `Vec`s of `n` polygons
+    fn chunks(self, n: uint) -> ChunksIterator<SRC>;
+}
+
+impl<P, T: Iterator<P>> Chunks<T> for T {
+    fn chunks(self, n: uint) -> ChunksIterator<T> {
+        assert!(n > 0);
+        ChunksIterator {
+            source: self,
+            size: n
+        }
+    }
+}
+
+/// an iterator that yields batches of polygons
+pub struct ChunksIterator<SRC> {
+    source: SRC,
+    size: uint
+}
+
+impl<P, SRC: Iterator<P>> Iterator<Vec<P>> for ChunksIterator<SRC> {
+    fn next(&mut self) -> Option<Vec<P>> {
+        let chunk: Vec<P> = self.source.by_ref().take(self.size).collect();
+        if chunk.is_empty() { None } else { Some(chunk) }
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let (lower, upper) = self.source.size_hint();
+        let chunks = |n: uint| (n + self.size - 1) / self.size;
+        (chunks(lower), upper.map(|n| chunks(n)))
+    }
+}
+
+/// Split a stream of `Polygon`s into its triangles and its quads in one
+/// pass, keeping the order of each. Useful right before filling separate
+/// index buffers for each kind.
+pub trait PartitionKinds<T> {
+    /// collect the triangles and the quads of the stream
+    fn partition_kinds(self) -> (Vec<Triangle<T>>, Vec<Quad<T>>);
+}
+
+impl<T, SRC: Iterator<Polygon<T>>> PartitionKinds<T> for SRC {
+    fn partition_kinds(self) -> (Vec<Triangle<T>>, Vec<Quad<T>>) {
+        let mut triangles = Vec::new();
+        let mut quads = Vec::new();
+        for p in self {
+            match p {
+                PolyTri(t) => triangles.push(t),
+                PolyQuad(q) => quads.push(q)
+            }
+        }
+        (triangles, quads)
+    }
+}
+
+/// Sort a stream of polygons into groups by a material attached to every
+/// vertex, such as one added with `with_attribute`. The material is read
+/// from the first vertex of each polygon only, so it must be the same for
+/// all of its vertices. This is useful for writing one group per material
+/// to formats like OBJ.
+pub trait GroupByMaterial<V, M> {
+    /// collect the polygons of each material with the material removed
+    /// from their vertices, keeping the order of the stream in each group
+    fn group_by_material(self) -> HashMap<M, Vec<Polygon<V>>>;
+}
+
+impl<V, M: Hash + Eq, SRC: Iterator<Polygon<(V, M)>>> GroupByMaterial<V, M> for SRC {
+    fn group_by_material(self) -> HashMap<M, Vec<Polygon<V>>> {
+        let mut groups: HashMap<M, Vec<Polygon<V>>> = HashMap::new();
+        for p in self {
+            let (m, p) = match p {
+                PolyTri(Triangle{x: (x, m), y: (y, _), z: (z, _)}) => {
+                    (m, PolyTri(Triangle::new(x, y, z)))
+                }
+                PolyQuad(Quad{x: (x, m), y: (y, _), z: (z, _), w: (w, _)}) => {
+                    (m, PolyQuad(Quad::new(x, y, z, w)))
+                }
+            };
+            if !groups.contains_key(&m) {
+                groups.insert(m, vec![p]);
+            } else {
+                groups.get_mut(&m).unwrap().push(p);
+            }
+        }
+        groups
+    }
+}
+
+/// Split the quads of a stream that are not flat into two triangles,
+/// see `Quad::is_planar`. Flat quads and triangles are passed through
+/// untouched, so geometry is only doubled where it has to be.
+pub trait SplitNonPlanar<SRC> {
+    /// split every quad whose fourth point is further than `eps` from
+    /// the plane of the other three
+    fn split_non_planar(self, eps: f32) -> SplitNonPlanarIterator<SRC>;
+}
+
+impl<P: IntoPolygon<Vector3>, T: Iterator<P>> SplitNonPlanar<T> for T {
+    fn split_non_planar(self, eps: f32) -> SplitNonPlanarIterator<T> {
+        SplitNonPlanarIterator {
+            source: self,
+            eps: eps,
+            second: None
+        }
+    }
+}
+
+/// an iterator that splits the quads that are not flat
+pub struct SplitNonPlanarIterator<SRC> {
+    source: SRC,
+    eps: f32,
+    // the second half of the last quad that was split
+    second: Option<Triangle<Vector3>>
+}
+
+impl<P: IntoPolygon<Vector3>, SRC: Iterator<P>> Iterator<Polygon<Vector3>> for SplitNonPlanarIterator<SRC> {
+    fn next(&mut self) -> Option<Polygon<Vector3>> {
+        match self.second.take() {
+            Some(t) => return Some(PolyTri(t)),
+            None => ()
+        }
+
+        self.source.next().map(|p| match p.into_polygon() {
+            PolyQuad(q) if !q.is_planar(self.eps) => {
+                let (a, b) = q.triangulate();
+                self.second = Some(b);
+                PolyTri(a)
+            }
+            p => p
+        })
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let extra = if self.second.is_some() { 1 } else { 0 };
+        let (lower, upper) = self.source.size_hint();
+        (lower + extra, upper.map(|n| 2 * n + extra))
+    }
+}
+
+/// Tag the vertices of each triangle with the barycentric basis vectors
+/// `(1, 0, 0)`, `(0, 1, 0)` and `(0, 0, 1)` in vertex order. A shader can
+/// use the interpolated tags to find how close a fragment is to an edge,
+/// for example to draw a wireframe. Polygons with more than three
+/// vertices have no single basis, pass them through `triangulate` first.
+pub trait Barycentric<SRC> {
+    /// pair each vertex of a triangle with its barycentric basis vector
+    fn barycentric(self) -> BarycentricIterator<SRC>;
+}
+
+impl<V, T: Iterator<Triangle<V>>> Barycentric<T> for T {
+    fn barycentric(self) -> BarycentricIterator<T> {
+        BarycentricIterator {
+            source: self
+        }
+    }
+}
+
+/// an iterator that tags each triangle vertex with its barycentric basis
+pub struct BarycentricIterator<SRC> {
+    source: SRC
+}
+
+impl<V, SRC: Iterator<Triangle<V>>> Iterator<Triangle<(V, Vector3)>> for BarycentricIterator<SRC> {
+    fn next(&mut self) -> Option<Triangle<(V, Vector3)>> {
+        self.source.next().map(|Triangle{x, y, z}| {
+            Triangle::new((x, (1., 0., 0.)),
+                          (y, (0., 1., 0.)),
+                          (z, (0., 0., 1.)))
+        })
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.source.size_hint()
+    }
+}
+
+/// Drive a stream of polygons through `sink` in windows of up to `window`
+/// polygons, rather than collecting the whole mesh. The same buffer is
+/// reused for every window, so no more than `window` polygons are held
+/// at once. `sink` is called for every full window and once more for
+/// any polygons left over at the end.
+pub fn stream_polygons<P, SRC: Iterator<P>>(src: SRC, window: uint, sink: |&[P]|) {
+    assert!(window > 0);
+    let mut src = src;
+    let mut buffer = Vec::with_capacity(window);
+    loop {
+        buffer.clear();
+        buffer.extend(src.by_ref().take(window));
+        if buffer.is_empty() {
+            return;
+        }
+        sink(buffer.as_slice());
+    }
+}
@@ -0,0 +1,181 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::f32::consts::PI_2;
+use std::num::FloatMath;
+use super::{Quad, Triangle, Polygon, MapVertex};
+use super::Polygon::{PolyTri, PolyQuad};
+use super::generators::{SharedVertex, IndexedPolygon};
+
+/// One of the three axes drawn by `Axes`.
+#[deriving(Copy, Clone, Show, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// the arrow pointing along +X
+    X,
+    /// the arrow pointing along +Y
+    Y,
+    /// the arrow pointing along +Z
+    Z
+}
+
+/// Three arrows of length 1 starting at (0, 0, 0) and pointing along
+/// +X, +Y and +Z, as used by editor gizmos. Each arrow is a capped
+/// shaft with a cone for a head, every vertex is tagged with the `Axis`
+/// of the arrow it belongs to.
+#[deriving(Copy)]
+pub struct Axes {
+    idx: uint,
+    sub_u: uint,
+    shaft_radius: f32,
+    head_radius: f32,
+    head_length: f32
+}
+
+impl Axes {
+    /// Create new axes.
+    /// `u` is the number of points around each arrow.
+    pub fn new(u: uint) -> Axes {
+        assert!(u > 2);
+        Axes {
+            idx: 0,
+            sub_u: u,
+            shaft_radius: 0.02,
+            head_radius: 0.06,
+            head_length: 0.2
+        }
+    }
+
+    /// Set the radius of the shaft of each arrow, the default is 0.02.
+    pub fn shaft_radius(mut self, radius: f32) -> Axes {
+        self.shaft_radius = radius;
+        self
+    }
+
+    /// Set the radius of the base of each cone, the default is 0.06.
+    pub fn head_radius(mut self, radius: f32) -> Axes {
+        self.head_radius = radius;
+        self
+    }
+
+    /// Set how much of the length of each arrow is taken by the cone,
+    /// the default is 0.2.
+    pub fn head_length(mut self, length: f32) -> Axes {
+        assert!(length > 0. && length < 1.);
+        self.head_length = length;
+        self
+    }
+
+    // the number of shared vertices in a single arrow
+    fn arrow_vertex_count(&self) -> uint {
+        3 * self.sub_u + 2
+    }
+
+    // the point `u` on a ring of radius `r` at distance `t` along the arrow
+    fn ring(&self, u: uint, r: f32, t: f32) -> (f32, f32, f32) {
+        let a = ((u % self.sub_u) as f32 / self.sub_u as f32) * PI_2;
+        (r * a.cos(), r * a.sin(), t)
+    }
+
+    // the vertex `idx` of an arrow pointing up the local z axis
+    fn local_vertex(&self, idx: uint) -> (f32, f32, f32) {
+        let neck = 1. - self.head_length;
+        if idx == 0 {
+            (0., 0., 0.)
+        } else if idx == self.arrow_vertex_count() - 1 {
+            (0., 0., 1.)
+        } else {
+            let idx = idx - 1;
+            let u = idx % self.sub_u;
+            match idx / self.sub_u {
+                0 => self.ring(u, self.shaft_radius, 0.),
+                1 => self.ring(u, self.shaft_radius, neck),
+                _ => self.ring(u, self.head_radius, neck)
+            }
+        }
+    }
+
+    // the shared index of the point `u` on ring `r` of the local arrow
+    fn index(&self, u: uint, r: uint) -> uint {
+        1 + r * self.sub_u + (u % self.sub_u)
+    }
+}
+
+impl Iterator<Polygon<((f32, f32, f32), Axis)>> for Axes {
+    fn next(&mut self) -> Option<Polygon<((f32, f32, f32), Axis)>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl SharedVertex<((f32, f32, f32), Axis)> for Axes {
+    fn shared_vertex(&self, idx: uint) -> ((f32, f32, f32), Axis) {
+        let count = self.arrow_vertex_count();
+        let (a, b, t) = self.local_vertex(idx % count);
+
+        // the axes are cyclic permutations of each other so the
+        // winding of the local arrow is kept
+        match idx / count {
+            0 => ((t, a, b), Axis::X),
+            1 => ((b, t, a), Axis::Y),
+            _ => ((a, b, t), Axis::Z)
+        }
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        3 * self.arrow_vertex_count()
+    }
+}
+
+impl IndexedPolygon<Polygon<uint>> for Axes {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        let per_arrow = 4 * self.sub_u;
+        let base = (idx / per_arrow) * self.arrow_vertex_count();
+        let idx = idx % per_arrow;
+        let u = idx % self.sub_u;
+
+        let p = match idx / self.sub_u {
+            // the cap on the end of the shaft faces backwards
+            0 => PolyTri(Triangle::new(0,
+                                       self.index(u+1, 0),
+                                       self.index(u, 0))),
+            1 => PolyQuad(Quad::new(self.index(u,   0),
+                                    self.index(u+1, 0),
+                                    self.index(u+1, 1),
+                                    self.index(u,   1))),
+            // the ring joining the shaft to the base of the cone
+            2 => PolyQuad(Quad::new(self.index(u,   1),
+                                    self.index(u+1, 1),
+                                    self.index(u+1, 2),
+                                    self.index(u,   2))),
+            _ => PolyTri(Triangle::new(self.index(u,   2),
+                                       self.index(u+1, 2),
+                                       self.arrow_vertex_count() - 1))
+        };
+        p.map_vertex(|i| base + i)
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        3 * 4 * self.sub_u
+    }
+}
@@ -0,0 +1,44 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use triangulate::EmitTriangles;
+use generators::{SharedVertex, IndexedPolygon};
+
+/// Collect a generator into a vertex buffer and a triangle index buffer,
+/// ready to be uploaded for drawing. The vertex buffer holds every shared
+/// vertex in order and each polygon adds the indices of its triangles,
+/// a quad is split in two and adds 6 indices.
+pub fn build_buffers<V, P: EmitTriangles<uint>, G: SharedVertex<V> + IndexedPolygon<P>>
+                    (generator: &G) -> (Vec<V>, Vec<uint>) {
+    build_buffers_with(generator, |v| v)
+}
+
+/// The same as `build_buffers` but every shared vertex is passed through
+/// `f` first. This can be used to fill the buffer with your own vertex
+/// type rather than the tuples produced by the generator.
+pub fn build_buffers_with<V, U, P: EmitTriangles<uint>, G: SharedVertex<V> + IndexedPolygon<P>>
+                         (generator: &G, f: |V| -> U) -> (Vec<U>, Vec<uint>) {
+    let vertices: Vec<U> = generator.shared_vertex_iter().map(f).collect();
+
+    let mut indices = Vec::with_capacity(generator.indexed_polygon_count() * 3);
+    for p in generator.indexed_polygon_iter() {
+        p.emit_triangles(|t| {
+            indices.push(t.x);
+            indices.push(t.y);
+            indices.push(t.z);
+        });
+    }
+
+    (vertices, indices)
+}
@@ -0,0 +1,150 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::cmp::max;
+use std::num::Float;
+
+use poly::Triangle;
+
+// the size of the simulated cache, the first three entries are the
+// vertices of the last triangle emitted
+static CACHE_SIZE: uint = 32;
+static CACHE_DECAY_POWER: f32 = 1.5;
+static LAST_TRI_SCORE: f32 = 0.75;
+static VALENCE_BOOST_SCALE: f32 = 2.0;
+static VALENCE_BOOST_POWER: f32 = 0.5;
+
+struct VertexData {
+    // position in the cache, `None` if the vertex is not cached
+    cache: Option<uint>,
+    // triangles using this vertex that have not been emitted yet
+    triangles: Vec<uint>,
+    score: f32
+}
+
+impl VertexData {
+    fn update_score(&mut self) {
+        if self.triangles.is_empty() {
+            self.score = -1.;
+            return;
+        }
+
+        let cache = match self.cache {
+            None => 0.,
+            Some(p) if p < 3 => LAST_TRI_SCORE,
+            Some(p) => {
+                let scale = 1. / (CACHE_SIZE - 3) as f32;
+                (1. - (p - 3) as f32 * scale).powf(CACHE_DECAY_POWER)
+            }
+        };
+
+        let valence = VALENCE_BOOST_SCALE *
+            (self.triangles.len() as f32).powf(-VALENCE_BOOST_POWER);
+        self.score = cache + valence;
+    }
+}
+
+fn triangle_score(vertices: &[VertexData], t: &Triangle<uint>) -> f32 {
+    vertices[t.x].score + vertices[t.y].score + vertices[t.z].score
+}
+
+/// Reorder a list of indexed triangles so that vertices are reused
+/// while they are still in the gpu's post-transform cache. This is Tom
+/// Forsyth's linear-speed vertex cache optimisation, the triangles
+/// returned are the same as the input but in a different order.
+///
+/// Quads and other polygons should be passed through `triangulate`
+/// before being optimized.
+pub fn optimize_vertex_cache(triangles: &[Triangle<uint>]) -> Vec<Triangle<uint>> {
+    let vertex_count = triangles.iter()
+        .map(|t| max(t.x, max(t.y, t.z)) + 1)
+        .max()
+        .unwrap_or(0);
+
+    let mut vertices: Vec<VertexData> = range(0, vertex_count).map(|_| {
+        VertexData { cache: None, triangles: Vec::new(), score: 0. }
+    }).collect();
+    for (i, t) in triangles.iter().enumerate() {
+        for &v in [t.x, t.y, t.z].iter() {
+            vertices[v].triangles.push(i);
+        }
+    }
+    for v in vertices.iter_mut() {
+        v.update_score();
+    }
+
+    let mut emitted = Vec::from_elem(triangles.len(), false);
+    let mut cache: Vec<uint> = Vec::new();
+    let mut output = Vec::with_capacity(triangles.len());
+    let mut best = None;
+
+    while output.len() < triangles.len() {
+        // nothing in the cache is usable, start again from the best
+        // triangle that has not been emitted
+        let next = match best {
+            Some(i) => i,
+            None => {
+                let mut found = None;
+                let mut score = -1.;
+                for (i, t) in triangles.iter().enumerate() {
+                    if emitted[i] { continue; }
+                    let s = triangle_score(vertices.as_slice(), t);
+                    if found.is_none() || s > score {
+                        found = Some(i);
+                        score = s;
+                    }
+                }
+                found.unwrap()
+            }
+        };
+
+        let t = triangles[next];
+        emitted[next] = true;
+        output.push(t);
+
+        // the triangle's vertices move to the front of the cache
+        let tri = [t.x, t.y, t.z];
+        for &v in tri.iter() {
+            let p = vertices[v].triangles.iter().position(|&i| i == next).unwrap();
+            vertices[v].triangles.swap_remove(p);
+        }
+        let mut updated: Vec<uint> = tri.iter().map(|&v| v).collect();
+        for &v in cache.iter() {
+            if !tri.contains(&v) {
+                updated.push(v);
+            }
+        }
+        for (p, &v) in updated.iter().enumerate() {
+            vertices[v].cache = if p < CACHE_SIZE { Some(p) } else { None };
+            vertices[v].update_score();
+        }
+        updated.truncate(CACHE_SIZE);
+        cache = updated;
+
+        // only triangles touching the cache can have changed score
+        best = None;
+        let mut score = -1.;
+        for &v in cache.iter() {
+            for &i in vertices[v].triangles.iter() {
+                let s = triangle_score(vertices.as_slice(), &triangles[i]);
+                if best.is_none() || s > score {
+                    best = Some(i);
+                    score = s;
+                }
+            }
+        }
+    }
+
+    output
+}
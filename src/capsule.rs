@@ -0,0 +1,141 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::f32::consts::{PI, PI_2};
+use std::num::FloatMath;
+use super::{Quad, Triangle, Polygon, MapVertex};
+use super::Polygon::{PolyTri, PolyQuad};
+use super::generators::{SharedVertex, IndexedPolygon};
+
+/// Represents a capsule centered at (0, 0, 0) pointing up (to 0, 0, 1).
+/// The capsule is a cylinder of `height` with a hemisphere of `radius`
+/// on each end, so it spans `height + 2 * radius` along the z axis.
+///
+/// The hemispheres end in a fan of triangles at the poles, the rest of
+/// the capsule is built from quads.
+#[deriving(Copy)]
+pub struct Capsule {
+    idx: uint,
+    radius: f32,
+    height: f32,
+    sub_u: uint,
+    sub_v: uint
+}
+
+impl Capsule {
+    /// Create a new capsule.
+    /// `u` is the number of points around the capsule.
+    /// `v` is the number of rings in each hemisphere, from the pole to
+    /// the edge of the cylinder.
+    pub fn new(radius: f32, height: f32, u: uint, v: uint) -> Capsule {
+        assert!(u > 2 && v > 0);
+        Capsule {
+            idx: 0,
+            radius: radius,
+            height: height,
+            sub_u: u,
+            sub_v: v
+        }
+    }
+
+    // the point `u` on `ring`, rings are counted from the top pole
+    // and the first `sub_v` rings belong to the upper hemisphere
+    fn vert(&self, u: uint, ring: uint) -> (f32, f32, f32) {
+        let (a, offset) = if ring < self.sub_v {
+            (ring + 1, self.height * 0.5)
+        } else {
+            (ring, -self.height * 0.5)
+        };
+        let a = (a as f32 / self.sub_v as f32) * PI * 0.5;
+        let u = ((u % self.sub_u) as f32 / self.sub_u as f32) * PI_2;
+
+        (u.cos() * a.sin() * self.radius,
+         u.sin() * a.sin() * self.radius,
+         a.cos() * self.radius + offset)
+    }
+
+    fn rings(&self) -> uint {
+        2 * self.sub_v
+    }
+
+    // the shared index of the point `u` on `ring`
+    fn index(&self, u: uint, ring: uint) -> uint {
+        1 + ring * self.sub_u + (u % self.sub_u)
+    }
+}
+
+impl Iterator<Polygon<(f32, f32, f32)>> for Capsule {
+    fn next(&mut self) -> Option<Polygon<(f32, f32, f32)>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl SharedVertex<(f32, f32, f32)> for Capsule {
+    fn shared_vertex(&self, idx: uint) -> (f32, f32, f32) {
+        let top = self.height * 0.5 + self.radius;
+        if idx == 0 {
+            (0., 0., top)
+        } else if idx == self.shared_vertex_count() - 1 {
+            (0., 0., -top)
+        } else {
+            let idx = idx - 1;
+            self.vert(idx % self.sub_u, idx / self.sub_u)
+        }
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        self.rings() * self.sub_u + 2
+    }
+}
+
+impl IndexedPolygon<Polygon<uint>> for Capsule {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        let u = idx % self.sub_u;
+        let v = idx / self.sub_u;
+
+        if v == 0 {
+            PolyTri(Triangle::new(0,
+                                  self.index(u,   0),
+                                  self.index(u+1, 0)))
+        } else if v == self.rings() {
+            let ring = self.rings() - 1;
+            PolyTri(Triangle::new(self.shared_vertex_count() - 1,
+                                  self.index(u+1, ring),
+                                  self.index(u,   ring)))
+        } else {
+            // the band between the `upper` and `upper+1` rings, the
+            // band in the middle is the side of the cylinder
+            let upper = v - 1;
+            PolyQuad(Quad::new(self.index(u,   upper),
+                               self.index(u,   upper+1),
+                               self.index(u+1, upper+1),
+                               self.index(u+1, upper)))
+        }
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        (self.rings() + 1) * self.sub_u
+    }
+}
@@ -0,0 +1,136 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::collections::HashMap;
+
+use {Quad, Polygon};
+use Polygon::PolyQuad;
+use neighbors::{Neighbors, polygon_edges};
+use math::{Vector3, add, scale};
+
+fn edge_key(a: uint, b: uint) -> (uint, uint) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+fn average(points: &[Vector3]) -> Vector3 {
+    let sum = points.iter().fold((0., 0., 0.), |acc, &p| add(acc, p));
+    scale(sum, 1. / points.len() as f32)
+}
+
+/// Apply one step of Catmull-Clark subdivision to a mesh of quads. Each
+/// quad is split into four around a new face point, with new edge points
+/// in the middle of each edge and the original vertices moved towards
+/// the limit surface.
+///
+/// Edges with only one face are treated as creases, their edge points
+/// are the midpoint of the edge and vertices along them only follow the
+/// boundary. Corners, vertices used by a single quad or that have more
+/// than two boundary edges, are not moved.
+///
+/// Every quad must have four distinct vertices.
+///
+/// The returned mesh keeps the original vertices at the same indices,
+/// followed by one face point for every quad and then the edge points.
+pub fn catmull_clark(vertices: Vec<Vector3>, quads: Vec<Quad<uint>>) -> (Vec<Vector3>, Vec<Quad<uint>>) {
+    let polygons: Vec<Polygon<uint>> = quads.iter().map(|&q| PolyQuad(q)).collect();
+    let mesh = Neighbors::new(vertices, polygons);
+    let vertices = mesh.vertices();
+    let face_base = vertices.len();
+    let edge_base = face_base + quads.len();
+
+    let face_points: Vec<Vector3> = quads.iter().map(|q| {
+        average(&[vertices[q.x], vertices[q.y], vertices[q.z], vertices[q.w]])
+    }).collect();
+
+    // give every edge an index in the order it is first seen
+    let mut edges = HashMap::new();
+    let mut edge_points = Vec::new();
+    for p in mesh.polygons().iter() {
+        for &(a, b) in polygon_edges(p).iter() {
+            let key = edge_key(a, b);
+            if edges.contains_key(&key) {
+                continue;
+            }
+
+            let faces = mesh.edge_polygons(a, b).unwrap();
+            let point = if faces.len() == 2 {
+                average(&[vertices[a], vertices[b],
+                          face_points[faces[0]], face_points[faces[1]]])
+            } else {
+                average(&[vertices[a], vertices[b]])
+            };
+            edges.insert(key, edge_base + edge_points.len());
+            edge_points.push(point);
+        }
+    }
+
+    let mut output = Vec::with_capacity(edge_base + edge_points.len());
+    for (v, &p) in vertices.iter().enumerate() {
+        let faces = match mesh.vertex_polygons(v) {
+            Some(faces) => faces,
+            None => {
+                output.push(p);
+                continue;
+            }
+        };
+
+        // the far end of every edge that touches `v`
+        let mut interior = Vec::new();
+        let mut boundary = Vec::new();
+        for &f in faces.iter() {
+            for &(a, b) in polygon_edges(&mesh.polygons()[f]).iter() {
+                let other = if a == v { b } else if b == v { a } else { continue };
+                if interior.contains(&other) || boundary.contains(&other) {
+                    continue;
+                }
+                if mesh.edge_polygons(a, b).unwrap().len() == 2 {
+                    interior.push(other);
+                } else {
+                    boundary.push(other);
+                }
+            }
+        }
+
+        let point = if boundary.is_empty() {
+            let n = faces.len() as f32;
+            let q = average(faces.iter().map(|&f| face_points[f]).collect::<Vec<Vector3>>().as_slice());
+            let r = average(interior.iter().map(|&o| {
+                scale(add(p, vertices[o]), 0.5)
+            }).collect::<Vec<Vector3>>().as_slice());
+            scale(add(add(q, scale(r, 2.)), scale(p, n - 3.)), 1. / n)
+        } else if boundary.len() == 2 && faces.len() > 1 {
+            let ends = add(vertices[boundary[0]], vertices[boundary[1]]);
+            add(scale(p, 0.75), scale(ends, 0.125))
+        } else {
+            p
+        };
+        output.push(point);
+    }
+    output.push_all(face_points.as_slice());
+    output.push_all(edge_points.as_slice());
+
+    let mut refined = Vec::with_capacity(quads.len() * 4);
+    for (f, q) in quads.iter().enumerate() {
+        let v = [q.x, q.y, q.z, q.w];
+        let e = |a: uint, b: uint| *edges.get(&edge_key(v[a], v[b])).unwrap();
+        for i in range(0u, 4) {
+            refined.push(Quad::new(v[i],
+                                   e(i, (i + 1) % 4),
+                                   face_base + f,
+                                   e((i + 3) % 4, i)));
+        }
+    }
+
+    (output, refined)
+}
@@ -0,0 +1,82 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::f32::consts::PI_2;
+use std::num::FloatMath;
+use super::{Triangle, Polygon, MapVertex};
+use super::Polygon::PolyTri;
+use super::generators::{SharedVertex, IndexedPolygon};
+
+/// Represents a filled circle with a radius of 1 on the XY plane,
+/// centered at (0, 0, 0) and facing up (to 0, 0, 1).
+/// The circle is built as a fan of triangles around the center.
+#[deriving(Copy)]
+pub struct Circle {
+    idx: uint,
+    sub_u: uint
+}
+
+impl Circle {
+    /// Create a new circle.
+    /// `u` is the number of points around the rim.
+    pub fn new(u: uint) -> Circle {
+        assert!(u > 2);
+        Circle {
+            idx: 0,
+            sub_u: u
+        }
+    }
+}
+
+impl Iterator<Polygon<(f32, f32, f32)>> for Circle {
+    fn next(&mut self) -> Option<Polygon<(f32, f32, f32)>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl SharedVertex<(f32, f32, f32)> for Circle {
+    fn shared_vertex(&self, idx: uint) -> (f32, f32, f32) {
+        if idx == 0 {
+            (0., 0., 0.)
+        } else {
+            let a = ((idx - 1) as f32 / self.sub_u as f32) * PI_2;
+            (a.cos(), a.sin(), 0.)
+        }
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        self.sub_u + 1
+    }
+}
+
+impl IndexedPolygon<Polygon<uint>> for Circle {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        PolyTri(Triangle::new(0, idx + 1, (idx + 1) % self.sub_u + 1))
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        self.sub_u
+    }
+}
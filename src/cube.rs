@@ -13,7 +13,8 @@
 //   limitations under the License.
 
 use core::iter::Range;
-use super::{MapVertex, Quad};
+use super::{MapVertex, Quad, Polygon};
+use super::Polygon::PolyQuad;
 use super::generators::{SharedVertex, IndexedPolygon};
 
 /// A perfect cube, centered at (0, 0, 0) with each face starting at 1/-1 away from the origin
@@ -28,6 +29,14 @@ impl Cube {
         Cube { range: range(0, 6) }
     }
 
+    /// Create a generator for the same cube that pairs each position
+    /// with a texture coordinate.
+    pub fn with_tex_coords(&self) -> CubeTexCoords {
+        CubeTexCoords {
+            idx: 0
+        }
+    }
+
     fn vert(&self, idx: uint) -> (f32, f32, f32) {
         let x = if idx & 4 == 4 { 1.} else { -1. };
         let y = if idx & 2 == 2 { 1.} else { -1. };
@@ -52,9 +61,13 @@ impl Cube {
     }
 } 
 
-impl Iterator<Quad<(f32, f32, f32)>> for Cube {
-    fn next(&mut self) -> Option<Quad<(f32, f32, f32)>> {
-        self.range.next().map(|idx| self.face(idx))
+impl Iterator<Polygon<(f32, f32, f32)>> for Cube {
+    fn next(&mut self) -> Option<Polygon<(f32, f32, f32)>> {
+        self.range.next().map(|idx| PolyQuad(self.face(idx)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.range.size_hint()
     }
 }
 
@@ -72,4 +85,63 @@ impl IndexedPolygon<Quad<uint>> for Cube {
     }
 
     fn indexed_polygon_count(&self) -> uint { 6 }
-}
\ No newline at end of file
+}
+/// A `Cube` where each vertex is paired with a texture coordinate
+/// `(u, v)`. Every face is mapped onto the whole unit square, so the
+/// corners are duplicated for each face with distinct texture coordinates.
+///
+/// The texture is upright on every face. On the four sides `v` increases
+/// towards `+z`, on the top and bottom `v` increases towards `+y`. The
+/// texture is not mirrored when seen from outside the cube.
+#[deriving(Copy)]
+pub struct CubeTexCoords {
+    idx: uint
+}
+
+/// the corners of each face in the order bottom left, bottom right,
+/// top right, top left as seen from outside the cube
+static FACES: [[uint, ..4], ..6] = [
+    [0b100, 0b110, 0b111, 0b101], // +x
+    [0b010, 0b000, 0b001, 0b011], // -x
+    [0b110, 0b010, 0b011, 0b111], // +y
+    [0b000, 0b100, 0b101, 0b001], // -y
+    [0b001, 0b101, 0b111, 0b011], // +z
+    [0b100, 0b000, 0b010, 0b110], // -z
+];
+
+static CORNERS: [(f32, f32), ..4] = [(0., 0.), (1., 0.), (1., 1.), (0., 1.)];
+
+impl Iterator<Polygon<((f32, f32, f32), (f32, f32))>> for CubeTexCoords {
+    fn next(&mut self) -> Option<Polygon<((f32, f32, f32), (f32, f32))>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl SharedVertex<((f32, f32, f32), (f32, f32))> for CubeTexCoords {
+    fn shared_vertex(&self, idx: uint) -> ((f32, f32, f32), (f32, f32)) {
+        let corner = FACES[idx / 4][idx % 4];
+        (Cube::new().vert(corner), CORNERS[idx % 4])
+    }
+
+    fn shared_vertex_count(&self) -> uint { 24 }
+}
+
+impl IndexedPolygon<Polygon<uint>> for CubeTexCoords {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        let i = idx * 4;
+        PolyQuad(Quad::new(i, i + 1, i + 2, i + 3))
+    }
+
+    fn indexed_polygon_count(&self) -> uint { 6 }
+}
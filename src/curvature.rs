@@ -0,0 +1,113 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::f32::consts::{PI_2, FRAC_PI_2};
+use std::num::FloatMath;
+
+use Polygon;
+use triangulate::EmitTriangles;
+use neighbors::{Neighbors, polygon_edges};
+use math::{Vector3, add, sub, scale, dot, cross, length};
+
+/// Estimate the mean and Gaussian curvature at each shared vertex of a
+/// mesh, returned as two vectors with one entry per vertex. Quads are
+/// split into triangles first.
+///
+/// The mean curvature comes from the cotangent weighted Laplace-Beltrami
+/// operator and is positive where the surface curves away from its front,
+/// as it does all over a sphere. The Gaussian curvature is the angle
+/// deficit around the vertex. Both are divided by the mixed Voronoi area
+/// of the vertex, so a unit sphere has a curvature of 1 for both.
+///
+/// Vertices on the boundary of an open mesh, and vertices that are not
+/// used by any polygon, have no estimate and are `None`.
+pub fn curvature(vertices: &[Vector3],
+                 polygons: &[Polygon<uint>]) -> (Vec<Option<f32>>, Vec<Option<f32>>) {
+    let n = vertices.len();
+    let mesh = Neighbors::new(vertices.to_vec(), polygons.to_vec());
+    let mut boundary = Vec::from_elem(n, false);
+    for p in polygons.iter() {
+        for &(a, b) in polygon_edges(p).iter() {
+            if mesh.edge_polygons(a, b).map(|f| f.len()) != Some(2) {
+                boundary[a] = true;
+                boundary[b] = true;
+            }
+        }
+    }
+
+    let mut laplace = Vec::from_elem(n, (0f32, 0f32, 0f32));
+    let mut normals = Vec::from_elem(n, (0f32, 0f32, 0f32));
+    let mut areas = Vec::from_elem(n, 0f32);
+    let mut angles = Vec::from_elem(n, 0f32);
+    for p in polygons.iter() {
+        p.emit_triangles(|t| {
+            let idx = [t.x, t.y, t.z];
+            let pos = [vertices[t.x], vertices[t.y], vertices[t.z]];
+            let normal = cross(sub(pos[1], pos[0]), sub(pos[2], pos[0]));
+            let twice_area = length(normal);
+            if twice_area == 0. {
+                return;
+            }
+
+            // the cotangent and the angle at each corner
+            let mut cot = [0f32, ..3];
+            let mut angle = [0f32, ..3];
+            for k in range(0u, 3) {
+                let u = sub(pos[(k + 1) % 3], pos[k]);
+                let w = sub(pos[(k + 2) % 3], pos[k]);
+                cot[k] = dot(u, w) / twice_area;
+                angle[k] = twice_area.atan2(dot(u, w));
+            }
+            let obtuse = angle.iter().any(|&a| a > FRAC_PI_2);
+
+            for k in range(0u, 3) {
+                let (j, l) = ((k + 1) % 3, (k + 2) % 3);
+                let (i, vj, vl) = (idx[k], idx[j], idx[l]);
+
+                // the edge across from corner `k` is weighted by its cotangent
+                let e = scale(sub(pos[j], pos[l]), cot[k]);
+                laplace[vj] = add(laplace[vj], e);
+                laplace[vl] = sub(laplace[vl], e);
+
+                angles[i] += angle[k];
+                normals[i] = add(normals[i], normal);
+                areas[i] += if !obtuse {
+                    let (ij, il) = (sub(pos[k], pos[j]), sub(pos[k], pos[l]));
+                    (dot(il, il) * cot[j] + dot(ij, ij) * cot[l]) / 8.
+                } else if angle[k] > FRAC_PI_2 {
+                    twice_area / 4.
+                } else {
+                    twice_area / 8.
+                };
+            }
+        });
+    }
+
+    let mut mean = Vec::with_capacity(n);
+    let mut gaussian = Vec::with_capacity(n);
+    for v in range(0, n) {
+        if boundary[v] || areas[v] == 0. {
+            mean.push(None);
+            gaussian.push(None);
+            continue;
+        }
+
+        // the operator is twice the mean curvature along the normal
+        let k = scale(laplace[v], 1. / (2. * areas[v]));
+        let h = length(k) / 2.;
+        mean.push(Some(if dot(k, normals[v]) < 0. { -h } else { h }));
+        gaussian.push(Some((PI_2 - angles[v]) / areas[v]));
+    }
+    (mean, gaussian)
+}
@@ -0,0 +1,142 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::f32::consts::PI_2;
+use std::num::FloatMath;
+use super::{Quad, Triangle, Polygon, MapVertex};
+use super::Polygon::{PolyTri, PolyQuad};
+use super::generators::{SharedVertex, IndexedPolygon};
+
+/// Represents a cylinder with radius of 1, height of 2,
+/// and centered at (0, 0, 0) pointing up (to 0, 0, 1).
+/// The ends of the cylinder are capped with a triangle fan, see `capped`
+/// for an open tube.
+#[deriving(Copy)]
+pub struct Cylinder {
+    idx: uint,
+    sub_u: uint,
+    sub_h: uint,
+    capped: bool
+}
+
+impl Cylinder {
+    /// Create a new cylinder.
+    /// `u` is the number of points across the radius.
+    pub fn new(u: uint) -> Cylinder {
+        Cylinder::subdivide(u, 1)
+    }
+
+    /// Create a new subdivided cylinder.
+    /// `u` is the number of points across the radius.
+    /// `h` is the number of segments along the height.
+    pub fn subdivide(u: uint, h: uint) -> Cylinder {
+        assert!(u > 1 && h > 0);
+        Cylinder {
+            idx: 0,
+            sub_u: u,
+            sub_h: h,
+            capped: true
+        }
+    }
+
+    /// Choose whether the ends are capped, the default is `true`. An
+    /// uncapped cylinder is only the side wall, it has no center vertices
+    /// and every polygon is a quad.
+    pub fn capped(self, capped: bool) -> Cylinder {
+        Cylinder {
+            capped: capped,
+            ..self
+        }
+    }
+
+    // the number of shared vertices used by each cap
+    fn caps(&self) -> uint {
+        if self.capped { 1 } else { 0 }
+    }
+
+    fn vert(&self, u: uint, h: uint) -> (f32, f32, f32) {
+        let a = ((u % self.sub_u) as f32 / self.sub_u as f32) * PI_2;
+        let z = (2. * h as f32) / self.sub_h as f32 - 1.;
+        (a.cos(), a.sin(), z)
+    }
+
+    // the shared index of the point `u` on ring `h`
+    fn index(&self, u: uint, h: uint) -> uint {
+        self.caps() + h * self.sub_u + (u % self.sub_u)
+    }
+}
+
+impl Iterator<Polygon<(f32, f32, f32)>> for Cylinder {
+    fn next(&mut self) -> Option<Polygon<(f32, f32, f32)>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl SharedVertex<(f32, f32, f32)> for Cylinder {
+    fn shared_vertex(&self, idx: uint) -> (f32, f32, f32) {
+        if self.capped && idx == 0 {
+            (0., 0., -1.)
+        } else if self.capped && idx == self.shared_vertex_count() - 1 {
+            (0., 0., 1.)
+        } else {
+            let idx = idx - self.caps();
+            self.vert(idx % self.sub_u, idx / self.sub_u)
+        }
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        (self.sub_h + 1) * self.sub_u + 2 * self.caps()
+    }
+}
+
+impl IndexedPolygon<Polygon<uint>> for Cylinder {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        let u = idx % self.sub_u;
+        // without caps the first row is already the side wall
+        let h = idx / self.sub_u + 1 - self.caps();
+
+        if h == 0 {
+            // the bottom cap faces down
+            PolyTri(Triangle::new(0,
+                                  self.index(u+1, 0),
+                                  self.index(u, 0)))
+        } else if h == self.sub_h + 1 {
+            // the top cap faces up
+            PolyTri(Triangle::new(self.shared_vertex_count() - 1,
+                                  self.index(u, self.sub_h),
+                                  self.index(u+1, self.sub_h)))
+        } else {
+            let h = h - 1;
+            PolyQuad(Quad::new(self.index(u,   h),
+                               self.index(u+1, h),
+                               self.index(u+1, h+1),
+                               self.index(u,   h+1)))
+        }
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        (self.sub_h + 2 * self.caps()) * self.sub_u
+    }
+}
@@ -0,0 +1,80 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::collections::HashMap;
+
+use {NGon, IntoPolygon, MapVertex, Centroid, Neighbors};
+use neighbors::polygon_edges;
+use math::Vector3;
+use generators::{SharedVertex, IndexedPolygon};
+
+/// Build the dual of a closed mesh. Every polygon of the mesh becomes a
+/// vertex placed at its centroid, and every vertex of the mesh becomes a
+/// polygon that joins the centroids of the polygons around it. The dual
+/// of a cube is an octahedron.
+///
+/// Vertex `i` of the dual is the centroid of polygon `i` of the mesh. The
+/// dual polygons keep the winding of the mesh. A vertex on the boundary
+/// of an open mesh is not surrounded by polygons, so it does not produce
+/// a polygon in the dual.
+pub fn dual<P: IntoPolygon<uint>,
+            G: SharedVertex<Vector3> + IndexedPolygon<P>>
+           (generator: &G) -> (Vec<Vector3>, Vec<NGon<uint>>) {
+    let polygons = generator.indexed_polygon_iter().map(|p| p.into_polygon()).collect();
+    let neighbors: Neighbors<Vector3> = Neighbors::new(generator.shared_vertex_iter().collect(), polygons);
+
+    let vertices: Vec<Vector3> = neighbors.polygons().iter().map(|p| {
+        p.clone().map_vertex(|i| neighbors.vertices()[i]).centroid()
+    }).collect();
+
+    // the polygon that walks each directed edge
+    let mut walks = HashMap::new();
+    for (i, p) in neighbors.polygons().iter().enumerate() {
+        for &edge in polygon_edges(p).iter() {
+            walks.insert(edge, i);
+        }
+    }
+
+    let mut duals = Vec::new();
+    for v in range(0, neighbors.vertices().len()) {
+        let start = match neighbors.vertex_polygons(v) {
+            Some(faces) if faces.len() >= 3 => faces[0],
+            _ => continue
+        };
+
+        // walk around `v` by crossing the edge that enters `v` in each
+        // polygon, the polygon on the other side walks it leaving `v`
+        let mut face = start;
+        let mut ring = Vec::new();
+        loop {
+            ring.push(face);
+            let prev = polygon_edges(&neighbors.polygons()[face]).iter()
+                                                                .find(|&&(_, b)| b == v)
+                                                                .map(|&(a, _)| a);
+            face = match prev.and_then(|a| walks.get(&(v, a))) {
+                Some(&f) => f,
+                None => break
+            };
+            if face == start {
+                duals.push(NGon::new(ring));
+                break;
+            }
+            if ring.len() > walks.len() {
+                break;
+            }
+        }
+    }
+
+    (vertices, duals)
+}
@@ -13,6 +13,7 @@
 //   limitations under the License.
 
 use std::iter::Range;
+use std::uint;
 
 /// The `SharedVertex` trait is meant to be used with the `IndexedPolygon` trait.
 /// This trait is meant as a way to calculate the shared vertices that are
@@ -77,4 +78,20 @@ impl<'a, T: IndexedPolygon<V>, V> Iterator<V> for IndexedPolygonIterator<'a, T,
     fn next(&mut self) -> Option<V> {
         self.idx.next().map(|idx| self.base.indexed_polygon(idx))
     }
-}
\ No newline at end of file
+}
+
+/// The index that ends one strip returned by `TriangleStrip` and starts
+/// the next. Use it as the primitive restart index when drawing.
+pub static PRIMITIVE_RESTART: uint = uint::MAX;
+
+/// `TriangleStrip` is implemented by the generators that are built from a
+/// regular grid, where the order of a strip follows from the rows of the
+/// grid. It is meant to be used with the `SharedVertex` trait.
+pub trait TriangleStrip {
+    /// return the indices for drawing the mesh as triangle strips, one
+    /// for each row of the grid, separated by `PRIMITIVE_RESTART`.
+    ///
+    /// Each quad is split along the same diagonal as `triangulate` would,
+    /// which takes an extra copy of the first index of every row.
+    fn triangle_strip(&self) -> Vec<uint>;
+}
@@ -0,0 +1,195 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::io::{Writer, IoResult, IoError, IoErrorKind};
+
+use triangulate::EmitTriangles;
+use measure::bounding_box;
+use math::Vector3;
+use generators::{SharedVertex, IndexedPolygon};
+
+static FLOAT: uint = 5126;
+static UNSIGNED_INT: uint = 5125;
+static ARRAY_BUFFER: uint = 34962;
+static ELEMENT_ARRAY_BUFFER: uint = 34963;
+static TRIANGLES: uint = 4;
+
+/// The attributes of a vertex that `to_gltf` knows how to write.
+pub trait GltfVertex {
+    /// the position of the vertex, written as `POSITION`
+    fn position(&self) -> Vector3;
+
+    /// the normal of the vertex, written as `NORMAL`
+    fn normal(&self) -> Option<Vector3> { None }
+
+    /// the texture coordinate of the vertex, written as `TEXCOORD_0`
+    fn tex_coord(&self) -> Option<(f32, f32)> { None }
+}
+
+impl GltfVertex for Vector3 {
+    fn position(&self) -> Vector3 { *self }
+}
+
+impl GltfVertex for (Vector3, Vector3) {
+    fn position(&self) -> Vector3 { let (p, _) = *self; p }
+    fn normal(&self) -> Option<Vector3> { let (_, n) = *self; Some(n) }
+}
+
+impl GltfVertex for (Vector3, (f32, f32)) {
+    fn position(&self) -> Vector3 { let (p, _) = *self; p }
+    fn tex_coord(&self) -> Option<(f32, f32)> { let (_, t) = *self; Some(t) }
+}
+
+impl GltfVertex for (Vector3, Vector3, (f32, f32)) {
+    fn position(&self) -> Vector3 { let (p, _, _) = *self; p }
+    fn normal(&self) -> Option<Vector3> { let (_, n, _) = *self; Some(n) }
+    fn tex_coord(&self) -> Option<(f32, f32)> { let (_, _, t) = *self; Some(t) }
+}
+
+// escapes `s` so it can be written inside a JSON string
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(format!("\\u{:04x}", c as u32).as_slice()),
+            c => out.push(c)
+        }
+    }
+    out
+}
+
+// adds a buffer view and an accessor over it, returning the accessor index
+fn add_accessor(views: &mut Vec<String>, accessors: &mut Vec<String>,
+                offset: uint, length: uint, target: uint,
+                component: uint, count: uint, kind: &str, bounds: &str) -> uint {
+    views.push(format!("{{\"buffer\":0,\"byteOffset\":{},\"byteLength\":{},\"target\":{}}}",
+                       offset, length, target));
+    accessors.push(format!("{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"{}\"{}}}",
+                           views.len() - 1, component, count, kind, bounds));
+    accessors.len() - 1
+}
+
+/// Write a mesh as a glTF 2.0 asset. The JSON document is written to
+/// `gltf` and the binary buffer it refers to, by `bin_uri`, is written
+/// to `bin`.
+///
+/// The asset holds a single node with one mesh and one primitive. The
+/// `NORMAL` and `TEXCOORD_0` attributes are only written if the vertex
+/// type has them. glTF has no quad mode, so quads are split in two along
+/// their `x`-`z` diagonal and the index buffer only holds triangles.
+///
+/// glTF does not allow empty buffers, so a mesh without any vertices or
+/// triangles is an `InvalidInput` error and nothing is written.
+pub fn to_gltf<W: Writer,
+               B: Writer,
+               V: GltfVertex,
+               P: EmitTriangles<uint>,
+               G: SharedVertex<V> + IndexedPolygon<P>>
+              (gltf: &mut W, bin: &mut B, bin_uri: &str, generator: &G) -> IoResult<()> {
+
+    let count = generator.shared_vertex_count();
+    let mut indices = Vec::new();
+    for p in generator.indexed_polygon_iter() {
+        p.emit_triangles(|t| {
+            indices.push(t.x as u32);
+            indices.push(t.y as u32);
+            indices.push(t.z as u32);
+        });
+    }
+
+    if count == 0 || indices.is_empty() {
+        return Err(IoError {
+            kind: IoErrorKind::InvalidInput,
+            desc: "a glTF mesh needs at least one triangle",
+            detail: None
+        });
+    }
+
+    let first = generator.shared_vertex(0);
+    let (has_normal, has_tex_coord) = (first.normal().is_some(), first.tex_coord().is_some());
+
+    // the buffer is packed as positions, normals, texture coordinates
+    // and finally the indices
+    for v in generator.shared_vertex_iter() {
+        let (x, y, z) = v.position();
+        try!(bin.write_le_f32(x));
+        try!(bin.write_le_f32(y));
+        try!(bin.write_le_f32(z));
+    }
+    if has_normal {
+        for v in generator.shared_vertex_iter() {
+            let (x, y, z) = v.normal().unwrap_or((0., 0., 0.));
+            try!(bin.write_le_f32(x));
+            try!(bin.write_le_f32(y));
+            try!(bin.write_le_f32(z));
+        }
+    }
+    if has_tex_coord {
+        for v in generator.shared_vertex_iter() {
+            let (u, v) = v.tex_coord().unwrap_or((0., 0.));
+            try!(bin.write_le_f32(u));
+            try!(bin.write_le_f32(v));
+        }
+    }
+    for &i in indices.iter() {
+        try!(bin.write_le_u32(i));
+    }
+
+    let ((min_x, min_y, min_z), (max_x, max_y, max_z)) =
+        bounding_box(generator.shared_vertex_iter().map(|v| v.position())).unwrap();
+    let bounds = format!(",\"min\":[{},{},{}],\"max\":[{},{},{}]",
+                         min_x, min_y, min_z, max_x, max_y, max_z);
+
+    let mut views = Vec::new();
+    let mut accessors = Vec::new();
+    let mut attributes = Vec::new();
+    let mut offset = 0u;
+
+    let position = add_accessor(&mut views, &mut accessors, offset, count * 12,
+                                ARRAY_BUFFER, FLOAT, count, "VEC3", bounds.as_slice());
+    attributes.push(format!("\"POSITION\":{}", position));
+    offset += count * 12;
+
+    if has_normal {
+        let normal = add_accessor(&mut views, &mut accessors, offset, count * 12,
+                                  ARRAY_BUFFER, FLOAT, count, "VEC3", "");
+        attributes.push(format!("\"NORMAL\":{}", normal));
+        offset += count * 12;
+    }
+
+    if has_tex_coord {
+        let tex_coord = add_accessor(&mut views, &mut accessors, offset, count * 8,
+                                     ARRAY_BUFFER, FLOAT, count, "VEC2", "");
+        attributes.push(format!("\"TEXCOORD_0\":{}", tex_coord));
+        offset += count * 8;
+    }
+
+    let index = add_accessor(&mut views, &mut accessors, offset, indices.len() * 4,
+                             ELEMENT_ARRAY_BUFFER, UNSIGNED_INT, indices.len(), "SCALAR", "");
+    offset += indices.len() * 4;
+
+    try!(writeln!(gltf, "{{"));
+    try!(writeln!(gltf, "\"asset\":{{\"version\":\"2.0\",\"generator\":\"genmesh\"}},"));
+    try!(writeln!(gltf, "\"scene\":0,"));
+    try!(writeln!(gltf, "\"scenes\":[{{\"nodes\":[0]}}],"));
+    try!(writeln!(gltf, "\"nodes\":[{{\"mesh\":0}}],"));
+    try!(writeln!(gltf, "\"meshes\":[{{\"primitives\":[{{\"attributes\":{{{}}},\"indices\":{},\"mode\":{}}}]}}],",
+                  attributes.as_slice().connect(","), index, TRIANGLES));
+    try!(writeln!(gltf, "\"buffers\":[{{\"uri\":\"{}\",\"byteLength\":{}}}],", escape(bin_uri), offset));
+    try!(writeln!(gltf, "\"bufferViews\":[{}],", views.as_slice().connect(",")));
+    try!(writeln!(gltf, "\"accessors\":[{}]", accessors.as_slice().connect(",")));
+    writeln!(gltf, "}}")
+}
@@ -0,0 +1,148 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::collections::HashMap;
+
+use Polygon;
+use neighbors::polygon_edges;
+
+/// One side of an edge of a `HalfEdgeMesh`, running along the boundary
+/// of a single face in its winding order.
+#[deriving(Copy, Clone, Show, PartialEq)]
+pub struct HalfEdge {
+    /// the vertex the half edge starts from
+    pub vertex: uint,
+    /// the face the half edge belongs to
+    pub face: uint,
+    /// the next half edge around the same face
+    pub next: uint,
+    /// the half edge running the other way along the same edge, `None`
+    /// if the edge is on the boundary of the mesh
+    pub twin: Option<uint>
+}
+
+/// A half edge view of an indexed mesh. Each polygon is broken into a
+/// loop of half edges and every half edge is paired with the half edge
+/// of the neighboring face that runs the other way along the same edge.
+///
+/// Only edges shared by exactly two faces with opposite windings are
+/// paired, any other edge is treated as part of the boundary.
+pub struct HalfEdgeMesh<T> {
+    vertices: Vec<T>,
+    faces: Vec<Polygon<uint>>,
+    // the first half edge of each face
+    face_edges: Vec<uint>,
+    edges: Vec<HalfEdge>
+}
+
+impl<T> HalfEdgeMesh<T> {
+    /// build the half edges of a mesh from its shared vertices and
+    /// indexed polygons.
+    pub fn new(vertices: Vec<T>, faces: Vec<Polygon<uint>>) -> HalfEdgeMesh<T> {
+        let mut edges = Vec::new();
+        let mut face_edges = Vec::with_capacity(faces.len());
+        let mut undirected: HashMap<(uint, uint), Vec<uint>> = HashMap::new();
+
+        for (f, p) in faces.iter().enumerate() {
+            let start = edges.len();
+            let boundary = polygon_edges(p);
+            face_edges.push(start);
+
+            for (i, &(a, b)) in boundary.iter().enumerate() {
+                let e = edges.len();
+                edges.push(HalfEdge {
+                    vertex: a,
+                    face: f,
+                    next: start + (i + 1) % boundary.len(),
+                    twin: None
+                });
+
+                if a == b {
+                    continue;
+                }
+
+                let key = if a < b { (a, b) } else { (b, a) };
+                if !undirected.contains_key(&key) {
+                    undirected.insert(key, Vec::new());
+                }
+                undirected.get_mut(&key).unwrap().push(e);
+            }
+        }
+
+        for (_, shared) in undirected.iter() {
+            if shared.len() != 2 {
+                continue;
+            }
+
+            let (a, b) = (shared[0], shared[1]);
+            if edges[a].vertex != edges[b].vertex {
+                edges[a].twin = Some(b);
+                edges[b].twin = Some(a);
+            }
+        }
+
+        HalfEdgeMesh {
+            vertices: vertices,
+            faces: faces,
+            face_edges: face_edges,
+            edges: edges
+        }
+    }
+
+    /// the polygons of the mesh
+    pub fn faces(&self) -> &[Polygon<uint>] {
+        self.faces.as_slice()
+    }
+
+    /// the shared vertices of the mesh
+    pub fn vertices(&self) -> &[T] {
+        self.vertices.as_slice()
+    }
+
+    /// every half edge of the mesh, the half edges of each face are
+    /// stored together in winding order
+    pub fn edges(&self) -> &[HalfEdge] {
+        self.edges.as_slice()
+    }
+
+    /// the first half edge of the face `f`
+    pub fn face_edge(&self, f: uint) -> uint {
+        self.face_edges[f]
+    }
+
+    /// the half edge running the other way along the edge of `e`
+    pub fn twin(&self, e: uint) -> Option<uint> {
+        self.edges[e].twin
+    }
+
+    /// the half edge that follows `e` around its face
+    pub fn next(&self, e: uint) -> uint {
+        self.edges[e].next
+    }
+
+    /// the vertex the half edge `e` starts from
+    pub fn vertex(&self, e: uint) -> uint {
+        self.edges[e].vertex
+    }
+
+    /// the face the half edge `e` belongs to
+    pub fn face(&self, e: uint) -> uint {
+        self.edges[e].face
+    }
+
+    /// true if `e` is on the boundary of the mesh and has no twin
+    pub fn is_boundary(&self, e: uint) -> bool {
+        self.edges[e].twin.is_none()
+    }
+}
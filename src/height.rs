@@ -0,0 +1,83 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use super::{Polygon, MapVertex};
+use super::Polygon::PolyQuad;
+use super::generators::{SharedVertex, IndexedPolygon, Plane};
+
+/// A subdivided `Plane` where the height of each point is read from a
+/// function of its `x` and `y` position, useful for building terrain.
+/// The function can be a closure, so it can sample a heightmap that was
+/// loaded at runtime.
+///
+/// The height is only sampled at the shared vertices of the plane, so
+/// neighboring quads always agree on the height of their corners.
+#[deriving(Copy)]
+pub struct HeightGrid<F> {
+    idx: uint,
+    plane: Plane,
+    height: F
+}
+
+impl<F: Fn(f32, f32) -> f32> HeightGrid<F> {
+    /// Create a new grid.
+    /// `x` is the number of subdivisions in the x axis
+    /// `y` is the number of subdivisions in the y axis
+    /// `height` is called with each `x` and `y` in -1 to 1
+    pub fn new(x: uint, y: uint, height: F) -> HeightGrid<F> {
+        HeightGrid {
+            idx: 0,
+            plane: Plane::subdivide(x, y),
+            height: height
+        }
+    }
+}
+
+impl<F: Fn(f32, f32) -> f32> Iterator<Polygon<(f32, f32, f32)>> for HeightGrid<F> {
+    fn next(&mut self) -> Option<Polygon<(f32, f32, f32)>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl<F: Fn(f32, f32) -> f32> SharedVertex<(f32, f32, f32)> for HeightGrid<F> {
+    fn shared_vertex(&self, idx: uint) -> (f32, f32, f32) {
+        let (x, y, _) = self.plane.shared_vertex(idx);
+        (x, y, (self.height)(x, y))
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        self.plane.shared_vertex_count()
+    }
+}
+
+impl<F: Fn(f32, f32) -> f32> IndexedPolygon<Polygon<uint>> for HeightGrid<F> {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        PolyQuad(self.plane.indexed_polygon(idx))
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        self.plane.indexed_polygon_count()
+    }
+}
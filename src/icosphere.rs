@@ -0,0 +1,145 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::collections::HashMap;
+use std::num::Float;
+use super::{Triangle, Polygon, MapVertex};
+use super::Polygon::PolyTri;
+use super::generators::{SharedVertex, IndexedPolygon};
+use math::{Vector3, add, scale, normalize};
+
+/// A sphere with radius of 1, centered at (0, 0, 0), built by repeatedly
+/// subdividing an icosahedron. Unlike `SphereUV` every triangle is close
+/// to the same shape and size.
+pub struct IcoSphere {
+    i: uint,
+    vertices: Vec<Vector3>,
+    faces: Vec<Triangle<uint>>
+}
+
+impl IcoSphere {
+    /// create a unit icosahedron
+    pub fn new() -> IcoSphere {
+        let t = (1. + 5f32.sqrt()) / 2.;
+        let vertices = vec![
+            (-1.,  t, 0.), ( 1.,  t, 0.), (-1., -t, 0.), ( 1., -t, 0.),
+            (0., -1.,  t), (0.,  1.,  t), (0., -1., -t), (0.,  1., -t),
+            ( t, 0., -1.), ( t, 0.,  1.), (-t, 0., -1.), (-t, 0.,  1.)
+        ];
+
+        let faces = vec![
+            Triangle::new(0, 11, 5), Triangle::new(0, 5, 1),
+            Triangle::new(0, 1, 7), Triangle::new(0, 7, 10),
+            Triangle::new(0, 10, 11), Triangle::new(1, 5, 9),
+            Triangle::new(5, 11, 4), Triangle::new(11, 10, 2),
+            Triangle::new(10, 7, 6), Triangle::new(7, 1, 8),
+            Triangle::new(3, 9, 4), Triangle::new(3, 4, 2),
+            Triangle::new(3, 2, 6), Triangle::new(3, 6, 8),
+            Triangle::new(3, 8, 9), Triangle::new(4, 9, 5),
+            Triangle::new(2, 4, 11), Triangle::new(6, 2, 10),
+            Triangle::new(8, 6, 7), Triangle::new(9, 8, 1)
+        ];
+
+        IcoSphere {
+            i: 0,
+            vertices: vertices.into_iter().map(|v| normalize(v)).collect(),
+            faces: faces
+        }
+    }
+
+    /// create an icosahedron that has been subdivided `n` times. Each
+    /// subdivision splits every triangle into four, so the sphere will have
+    /// `20 * 4^n` faces.
+    pub fn subdivide(n: uint) -> IcoSphere {
+        let mut sphere = IcoSphere::new();
+        for _ in range(0, n) {
+            sphere.split();
+        }
+        sphere
+    }
+
+    fn split(&mut self) {
+        // maps an edge to the vertex that was placed at its midpoint, so
+        // a midpoint is shared by the two triangles on either side of the edge
+        let mut midpoints = HashMap::new();
+        let mut faces = Vec::with_capacity(self.faces.len() * 4);
+
+        for &Triangle{x, y, z} in self.faces.iter() {
+            let xy = midpoint(&mut self.vertices, &mut midpoints, x, y);
+            let yz = midpoint(&mut self.vertices, &mut midpoints, y, z);
+            let zx = midpoint(&mut self.vertices, &mut midpoints, z, x);
+
+            faces.push(Triangle::new(x, xy, zx));
+            faces.push(Triangle::new(y, yz, xy));
+            faces.push(Triangle::new(z, zx, yz));
+            faces.push(Triangle::new(xy, yz, zx));
+        }
+
+        self.faces = faces;
+    }
+}
+
+/// find or create the vertex that lies between `a` and `b` on the sphere
+fn midpoint(vertices: &mut Vec<Vector3>,
+            midpoints: &mut HashMap<(uint, uint), uint>,
+            a: uint, b: uint) -> uint {
+    let key = if a < b { (a, b) } else { (b, a) };
+    match midpoints.get(&key) {
+        Some(&idx) => return idx,
+        None => ()
+    }
+
+    let idx = vertices.len();
+    let v = scale(add(vertices[a], vertices[b]), 0.5);
+    vertices.push(normalize(v));
+    midpoints.insert(key, idx);
+    idx
+}
+
+impl Iterator<Polygon<Vector3>> for IcoSphere {
+    fn next(&mut self) -> Option<Polygon<Vector3>> {
+        if self.i == self.faces.len() {
+            return None;
+        }
+
+        let idx = self.i;
+        self.i += 1;
+        Some(PolyTri(self.faces[idx].map_vertex(|i| self.vertices[i])))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.faces.len() - self.i;
+        (n, Some(n))
+    }
+}
+
+impl SharedVertex<Vector3> for IcoSphere {
+    fn shared_vertex(&self, idx: uint) -> Vector3 {
+        self.vertices[idx]
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        self.vertices.len()
+    }
+}
+
+impl IndexedPolygon<Triangle<uint>> for IcoSphere {
+    fn indexed_polygon(&self, idx: uint) -> Triangle<uint> {
+        self.faces[idx]
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        self.faces.len()
+    }
+}
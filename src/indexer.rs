@@ -12,6 +12,9 @@
 //   See the License for the specific language governing permissions and
 //   limitations under the License.
 
+use std::collections::HashMap;
+use std::hash::Hash;
+
 /// A trait defining how to defined an Indexer. An indexer is a object
 /// that collects verticies and emits indexes for the vertex. The intent
 /// is that an Indexer can find redundent vertexes and deduplicate them
@@ -27,6 +30,12 @@ pub trait Indexer<T> {
 /// a new vertex and return the index of that new vertex.
 ///
 /// The oldest sample by time used will be dropped if a new vertex is found.
+///
+/// The size of the cache is a tradeoff between speed and correctness. The
+/// returned indices are always valid, but if a vertex has been dropped from
+/// the cache by the time it is seen again it will be emitted a second time.
+/// A cache that is at least as large as the number of unique vertices in the
+/// mesh guarantees that every vertex is emitted exactly once.
 pub struct LruIndexer<'a, T> {
     index: uint,
     max: uint,
@@ -79,3 +88,51 @@ impl<'a, T: PartialEq + Clone> Indexer<T> for LruIndexer<'a, T> {
         }
     }
 }
+
+/// A `HashIndexer` finds every duplicated vertex, so each distinct vertex
+/// is stored exactly once. The vertices are collected in the order they
+/// were first seen and can be read back after indexing.
+///
+/// Unlike the `LruIndexer` every distinct vertex is remembered, so the
+/// memory used grows with the size of the mesh rather than being bounded
+/// by a cache size. Vertices must be `Hash` and `Eq`, so this does not
+/// work directly with `f32` positions.
+pub struct HashIndexer<T> {
+    indices: HashMap<T, uint>,
+    vertices: Vec<T>
+}
+
+impl<T: Hash + Eq + Clone> HashIndexer<T> {
+    /// create a new empty `HashIndexer`
+    pub fn new() -> HashIndexer<T> {
+        HashIndexer {
+            indices: HashMap::new(),
+            vertices: Vec::new()
+        }
+    }
+
+    /// the distinct vertices seen so far, a vertex is at the offset of
+    /// the index that was returned for it
+    pub fn vertices(&self) -> &[T] {
+        self.vertices.as_slice()
+    }
+
+    /// consume the indexer returning the distinct vertices
+    pub fn into_vertices(self) -> Vec<T> {
+        self.vertices
+    }
+}
+
+impl<T: Hash + Eq + Clone> Indexer<T> for HashIndexer<T> {
+    fn index(&mut self, v: T) -> uint {
+        match self.indices.get(&v) {
+            Some(&idx) => return idx,
+            None => ()
+        }
+
+        let idx = self.vertices.len();
+        self.vertices.push(v.clone());
+        self.indices.insert(v, idx);
+        idx
+    }
+}
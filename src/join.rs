@@ -0,0 +1,72 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use super::MapVertex;
+use super::generators::{SharedVertex, IndexedPolygon};
+
+/// Combine two generators into one mesh. The shared vertices of `b`
+/// are placed after the shared vertices of `a`, and the indices of the
+/// polygons of `b` are offset to match.
+///
+/// More than two generators can be combined by nesting, for example
+/// `Join::new(a, Join::new(b, c))`. The polygon streams of generators
+/// can be combined with `chain`.
+#[deriving(Copy, Clone)]
+pub struct Join<A, B> {
+    a: A,
+    b: B
+}
+
+impl<A, B> Join<A, B> {
+    /// combine `a` and `b`, `a` comes first
+    pub fn new(a: A, b: B) -> Join<A, B> {
+        Join {
+            a: a,
+            b: b
+        }
+    }
+}
+
+impl<V, A: SharedVertex<V>, B: SharedVertex<V>> SharedVertex<V> for Join<A, B> {
+    fn shared_vertex(&self, idx: uint) -> V {
+        let offset = self.a.shared_vertex_count();
+        if idx < offset {
+            self.a.shared_vertex(idx)
+        } else {
+            self.b.shared_vertex(idx - offset)
+        }
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        self.a.shared_vertex_count() + self.b.shared_vertex_count()
+    }
+}
+
+impl<V, P: MapVertex<uint, uint, P>,
+        A: SharedVertex<V> + IndexedPolygon<P>,
+        B: SharedVertex<V> + IndexedPolygon<P>> IndexedPolygon<P> for Join<A, B> {
+    fn indexed_polygon(&self, idx: uint) -> P {
+        let count = self.a.indexed_polygon_count();
+        if idx < count {
+            self.a.indexed_polygon(idx)
+        } else {
+            let offset = self.a.shared_vertex_count();
+            self.b.indexed_polygon(idx - count).map_vertex(|i| i + offset)
+        }
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        self.a.indexed_polygon_count() + self.b.indexed_polygon_count()
+    }
+}
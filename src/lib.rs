@@ -25,51 +25,346 @@
 //! `collect` that are useful in themselves. `Genmesh` includes a number of traits that
 //! can be used with the built in `Iterator` traits to build the meshes that your engine
 //! needs.
+//!
+//! The polygon types and the vertex adapters in the crate root only need
+//! `core` and `collections`. Everything else, including the generators
+//! that need `FloatMath` and the file format helpers, needs the default
+//! `std` feature. Build with `--no-default-features` to leave it out.
 
 #![deny(missing_docs)]
+#![feature(unboxed_closures)]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(feature = "std"), feature(phase, globs))]
+// some of the math helpers are only used by the `std` modules
+#![cfg_attr(not(feature = "std"), allow(dead_code))]
 
+#[cfg(feature = "std")]
+extern crate core;
+#[cfg(not(feature = "std"))]
+#[phase(plugin, link)]
 extern crate core;
+#[cfg(not(feature = "std"))]
+extern crate collections;
+
+// without `std` the paths used by `deriving` and by the core modules are
+// pointed at `core` and `collections` instead
+#[cfg(not(feature = "std"))]
+mod std {
+    pub use core::{clone, cmp, fmt, hash, iter, kinds, num, option};
+    pub use collections::vec;
+
+    pub mod collections {
+        pub use collections::RingBuf;
+    }
+}
+#[cfg(feature = "serialize")]
+extern crate serialize;
 
 pub use poly::{
     Quad,
     Triangle,
     Polygon,
+    NGon,
+    Tagged,
+    EmitVertices,
+    EmitVerticesRef,
+    IntoVertices,
+    IntoVerticesIterator,
     Vertices,
     VerticesIterator,
+    TriangleVertices,
+    TriangleVerticesIterator,
+    EnumerateVertices,
     MapToVertices,
-    MapVertex
+    MapVertex,
+    MapVertexRef,
+    MapToVerticesRef,
+    IntoPolygon
 };
 
+#[cfg(feature = "std")]
 pub use triangulate::{
     EmitTriangles,
     Triangulate,
     TriangulateIterator
 };
 
+#[cfg(feature = "std")]
 pub use indexer::{
     Indexer,
-    LruIndexer
+    LruIndexer,
+    HashIndexer
+};
+
+#[cfg(feature = "std")]
+pub use normal::{
+    FaceNormal,
+    generate_normals,
+    generate_crease_normals,
+    ColorByNormal,
+    ColorByNormalIterator
+};
+
+#[cfg(feature = "std")]
+pub use tangent::generate_tangents;
+
+#[cfg(feature = "std")]
+pub use neighbors::Neighbors;
+
+#[cfg(feature = "std")]
+pub use halfedge::{HalfEdge, HalfEdgeMesh};
+
+#[cfg(feature = "std")]
+pub use topology::{Topology, topology};
+
+#[cfg(feature = "std")]
+pub use dual::dual;
+
+#[cfg(feature = "std")]
+pub use catmull::catmull_clark;
+
+#[cfg(feature = "std")]
+pub use simplify::simplify;
+
+#[cfg(feature = "std")]
+pub use merge::merge_coplanar;
+
+#[cfg(feature = "std")]
+pub use curvature::curvature;
+
+#[cfg(feature = "std")]
+pub use lines::{
+    Line,
+    Primitive,
+    EmitLines,
+    Lines,
+    LinesIterator,
+    UniqueLines,
+    UniqueLinesIterator,
+    unique_edges
+};
+
+#[cfg(feature = "std")]
+pub use measure::{
+    Centroid,
+    Area,
+    MeasurePolygons,
+    BoundingSphere,
+    bounding_box,
+    TriangleQuality,
+    triangle_quality
 };
 
+#[cfg(feature = "std")]
+pub use subdivide::{
+    SubdivideTriangles,
+    SubdivideTrianglesIterator
+};
+
+#[cfg(feature = "std")]
+pub use winding::{
+    Winding,
+    check_winding,
+    ReverseWinding,
+    Reverse,
+    ReverseIterator
+};
+
+#[cfg(feature = "std")]
+pub use transform::{
+    Matrix4,
+    Transform,
+    TransformIterator,
+    Spherify,
+    SpherifyIterator,
+    Instances,
+    TransformUv,
+    TransformUvIterator
+};
+
+#[cfg(feature = "std")]
+pub use adapter::{
+    FlatMapPolygons,
+    FlatMapPolygonsIterator,
+    MapToPolygons,
+    MapToPolygonsIterator,
+    FaceIndex,
+    FaceIndexIterator,
+    WithAttribute,
+    WithAttributeIterator,
+    InspectPolygons,
+    InspectPolygonsIterator,
+    Chunks,
+    ChunksIterator,
+    FoldPolygons,
+    PartitionKinds,
+    GroupByMaterial,
+    SplitNonPlanar,
+    SplitNonPlanarIterator,
+    Barycentric,
+    BarycentricIterator,
+    stream_polygons
+};
+
+#[cfg(feature = "std")]
+pub use reindex::{remap_indices, compact};
+
+#[cfg(feature = "std")]
+pub use buffer::{build_buffers, build_buffers_with};
+
+#[cfg(feature = "std")]
+pub use cache::optimize_vertex_cache;
+
+#[cfg(feature = "std")]
+pub use weld::weld;
+
+#[cfg(feature = "std")]
+pub use zip::{
+    ZipPolygon,
+    ZipVertex,
+    ZipVertexIterator
+};
+
+#[cfg(feature = "std")]
+mod adapter;
+#[cfg(feature = "std")]
+mod buffer;
+#[cfg(feature = "std")]
+mod cache;
+#[cfg(feature = "std")]
 mod triangulate;
 mod poly;
+#[cfg(feature = "std")]
 mod indexer;
+#[cfg(feature = "std")]
 mod generator;
+#[cfg(feature = "std")]
+mod normal;
+#[cfg(feature = "std")]
+mod neighbors;
+#[cfg(feature = "std")]
+mod halfedge;
+#[cfg(feature = "std")]
+mod topology;
+#[cfg(feature = "std")]
+mod dual;
+#[cfg(feature = "std")]
+mod catmull;
+#[cfg(feature = "std")]
+mod simplify;
+#[cfg(feature = "std")]
+mod merge;
+#[cfg(feature = "std")]
+mod curvature;
+#[cfg(feature = "std")]
+mod lines;
+#[cfg(feature = "std")]
+mod winding;
+#[cfg(feature = "std")]
+mod gltf;
+#[cfg(feature = "std")]
+mod obj;
+#[cfg(feature = "std")]
+mod stl;
+mod math;
+#[cfg(feature = "std")]
+mod measure;
+#[cfg(feature = "std")]
+mod subdivide;
+#[cfg(feature = "std")]
+mod tangent;
+#[cfg(feature = "std")]
+mod reindex;
+#[cfg(feature = "std")]
+mod transform;
+#[cfg(feature = "std")]
+mod weld;
+#[cfg(feature = "std")]
+mod zip;
 
+#[cfg(feature = "std")]
+mod axes;
+#[cfg(feature = "std")]
+mod capsule;
+#[cfg(feature = "std")]
+mod circle;
+#[cfg(feature = "std")]
 mod cube;
+#[cfg(feature = "std")]
+mod cylinder;
+#[cfg(feature = "std")]
+mod height;
+#[cfg(feature = "std")]
+mod icosphere;
+#[cfg(feature = "std")]
+mod join;
+#[cfg(feature = "std")]
+mod mobius;
+#[cfg(feature = "std")]
+mod octahedron;
+#[cfg(feature = "std")]
+mod parametric;
+#[cfg(feature = "std")]
 mod plane;
+#[cfg(feature = "std")]
+mod rounded;
+#[cfg(feature = "std")]
 mod sphere;
+#[cfg(feature = "std")]
+mod spring;
+#[cfg(feature = "std")]
+mod tetrahedron;
+#[cfg(feature = "std")]
+mod torus;
 
 /// a collection of utilties that can be used to build
 /// meshes programmatically
+#[cfg(feature = "std")]
 pub mod generators {
     pub use generator::{
         SharedVertex,
         IndexedPolygon,
         SharedVertexIterator,
         IndexedPolygonIterator,
+        TriangleStrip,
+        PRIMITIVE_RESTART
+    };
+    pub use axes::{Axes, Axis};
+    pub use capsule::Capsule;
+    pub use circle::Circle;
+    pub use cube::{Cube, CubeTexCoords};
+    pub use cylinder::Cylinder;
+    pub use height::HeightGrid;
+    pub use icosphere::IcoSphere;
+    pub use join::Join;
+    pub use mobius::Mobius;
+    pub use octahedron::Octahedron;
+    pub use parametric::ParametricSurface;
+    pub use plane::{Plane, Orientation};
+    pub use rounded::RoundedBox;
+    pub use sphere::{
+        SphereUV,
+        GenericSphereUV,
+        SphereUVBuilder,
+        SphereUVTexCoords,
+        SphereUVNormals,
+        PoleStyle
     };
-    pub use cube::Cube;
-    pub use plane::Plane;
-    pub use sphere::SphereUV;
+    pub use spring::Spring;
+    pub use tetrahedron::Tetrahedron;
+    pub use torus::Torus;
+}
+
+/// functions for writing meshes out to common file formats
+#[cfg(feature = "std")]
+pub mod export {
+    pub use obj::to_obj;
+    pub use stl::to_stl_binary;
+    pub use gltf::{to_gltf, GltfVertex};
+}
+
+/// functions for reading meshes from common file formats
+#[cfg(feature = "std")]
+pub mod import {
+    pub use obj::from_obj;
 }
@@ -0,0 +1,254 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::collections::{RingBuf, HashSet};
+use std::cmp;
+
+use Polygon::{PolyTri, PolyQuad};
+use self::Primitive::{PrimPolygon, PrimLine};
+use {
+    Quad,
+    Triangle,
+    Polygon,
+    EmitVertices,
+    EmitVerticesRef,
+    MapVertex,
+    MapVertexRef,
+};
+
+/// A line between two points. Maps to `GL_LINES`
+#[deriving(Clone, Show, PartialEq, Eq, Hash, Copy)]
+pub struct Line<T> {
+    /// the first point of a line
+    pub a: T,
+    /// the second point of a line
+    pub b: T,
+}
+
+impl<T> Line<T> {
+    /// create a new `Line` with supplied vertices
+    pub fn new(v0: T, v1: T) -> Line<T> {
+        Line {
+            a: v0,
+            b: v1
+        }
+    }
+}
+
+impl<T> EmitVertices<T> for Line<T> {
+    fn emit_vertices(self, emit: |T|) {
+        let Line{a, b} = self;
+        emit(a);
+        emit(b);
+    }
+}
+
+impl<T> EmitVerticesRef<T> for Line<T> {
+    fn emit_vertices_ref(&self, emit: |&T|) {
+        emit(&self.a);
+        emit(&self.b);
+    }
+}
+
+impl<T, U> MapVertex<T, U, Line<U>> for Line<T> {
+    fn map_vertex(self, map: |T| -> U) -> Line<U> {
+        let Line{a, b} = self;
+        Line {
+            a: map(a),
+            b: map(b)
+        }
+    }
+}
+
+impl<T, U> MapVertexRef<T, U, Line<U>> for Line<T> {
+    fn map_vertex_ref(&self, map: |&T| -> U) -> Line<U> {
+        Line {
+            a: map(&self.a),
+            b: map(&self.b)
+        }
+    }
+}
+
+/// Either a polygon or a line, so a single stream can mix filled faces
+/// with lines, like a mesh drawn together with its normals.
+#[deriving(Clone, Show, PartialEq, Eq, Hash, Copy)]
+pub enum Primitive<T> {
+    /// a filled polygon
+    PrimPolygon(Polygon<T>),
+    /// a line between two points
+    PrimLine(Line<T>)
+}
+
+impl<T> EmitVertices<T> for Primitive<T> {
+    fn emit_vertices(self, emit: |T|) {
+        match self {
+            PrimPolygon(p) => p.emit_vertices(emit),
+            PrimLine(l) => l.emit_vertices(emit)
+        }
+    }
+}
+
+impl<T> EmitVerticesRef<T> for Primitive<T> {
+    fn emit_vertices_ref(&self, emit: |&T|) {
+        match self {
+            &PrimPolygon(ref p) => p.emit_vertices_ref(emit),
+            &PrimLine(ref l) => l.emit_vertices_ref(emit)
+        }
+    }
+}
+
+impl<T: Clone, U> MapVertex<T, U, Primitive<U>> for Primitive<T> {
+    fn map_vertex(self, map: |T| -> U) -> Primitive<U> {
+        match self {
+            PrimPolygon(p) => PrimPolygon(p.map_vertex(map)),
+            PrimLine(l) => PrimLine(l.map_vertex(map))
+        }
+    }
+}
+
+impl<T, U> MapVertexRef<T, U, Primitive<U>> for Primitive<T> {
+    fn map_vertex_ref(&self, map: |&T| -> U) -> Primitive<U> {
+        match self {
+            &PrimPolygon(ref p) => PrimPolygon(p.map_vertex_ref(map)),
+            &PrimLine(ref l) => PrimLine(l.map_vertex_ref(map))
+        }
+    }
+}
+
+/// Break a polygon down into the lines along its boundary.
+pub trait EmitLines<T> {
+    /// emit each edge of the polygon in winding order, the last
+    /// line ends where the first line started
+    fn emit_lines(self, emit: |Line<T>|);
+}
+
+impl<T: Clone> EmitLines<T> for Triangle<T> {
+    fn emit_lines(self, emit: |Line<T>|) {
+        let Triangle{x, y, z} = self;
+        emit(Line::new(x.clone(), y.clone()));
+        emit(Line::new(y, z.clone()));
+        emit(Line::new(z, x));
+    }
+}
+
+impl<T: Clone> EmitLines<T> for Quad<T> {
+    fn emit_lines(self, emit: |Line<T>|) {
+        let Quad{x, y, z, w} = self;
+        emit(Line::new(x.clone(), y.clone()));
+        emit(Line::new(y, z.clone()));
+        emit(Line::new(z, w.clone()));
+        emit(Line::new(w, x));
+    }
+}
+
+impl<T: Clone> EmitLines<T> for Polygon<T> {
+    fn emit_lines(self, emit: |Line<T>|) {
+        match self {
+            PolyTri(p) => p.emit_lines(emit),
+            PolyQuad(p) => p.emit_lines(emit)
+        }
+    }
+}
+
+/// Convert a stream of polygons into a stream of the lines along the
+/// edge of each polygon. Useful for drawing a wireframe of a mesh.
+///
+/// An edge shared by two polygons is emitted once for each polygon,
+/// see `UniqueLines` to remove the duplicates from an indexed stream.
+pub trait Lines<SRC, V> {
+    /// convert a polygon iterator to a lines iterator.
+    fn lines(self) -> LinesIterator<SRC, V>;
+}
+
+impl<V, P: EmitLines<V>, T: Iterator<P>> Lines<T, V> for T {
+    fn lines(self) -> LinesIterator<T, V> {
+        LinesIterator {
+            source: self,
+            buffer: RingBuf::with_capacity(4)
+        }
+    }
+}
+
+/// an iterator that breaks a polygon down into its edges
+pub struct LinesIterator<SRC, V> {
+    source: SRC,
+    buffer: RingBuf<Line<V>>
+}
+
+impl<V, U: EmitLines<V>, SRC: Iterator<U>> Iterator<Line<V>> for LinesIterator<SRC, V> {
+    fn next(&mut self) -> Option<Line<V>> {
+        loop {
+            match self.buffer.pop_front() {
+                Some(v) => return Some(v),
+                None => ()
+            }
+
+            match self.source.next() {
+                Some(p) => p.emit_lines(|v| self.buffer.push_back(v)),
+                None => return None
+            }
+        }
+    }
+}
+
+/// Convert a stream of indexed polygons into the lines along their
+/// edges, emitting every undirected edge only once. The first time
+/// an edge is seen decides the direction of the line.
+pub trait UniqueLines<SRC> {
+    /// convert an indexed polygon iterator to a lines iterator
+    /// without duplicated edges
+    fn unique_lines(self) -> UniqueLinesIterator<SRC>;
+}
+
+impl<P: EmitLines<uint>, T: Iterator<P>> UniqueLines<T> for T {
+    fn unique_lines(self) -> UniqueLinesIterator<T> {
+        UniqueLinesIterator {
+            source: self.lines(),
+            seen: HashSet::new()
+        }
+    }
+}
+
+/// an iterator that yields each edge of an indexed mesh once
+pub struct UniqueLinesIterator<SRC> {
+    source: LinesIterator<SRC, uint>,
+    seen: HashSet<(uint, uint)>
+}
+
+impl<P: EmitLines<uint>, SRC: Iterator<P>> Iterator<Line<uint>> for UniqueLinesIterator<SRC> {
+    fn next(&mut self) -> Option<Line<uint>> {
+        loop {
+            let line = match self.source.next() {
+                Some(line) => line,
+                None => return None
+            };
+
+            let key = (cmp::min(line.a, line.b), cmp::max(line.a, line.b));
+            if self.seen.insert(key) {
+                return Some(line);
+            }
+        }
+    }
+}
+
+/// Collect the unique undirected edges of a list of indexed polygons,
+/// each edge is returned once as `(min, max)` in the order it was first
+/// seen. Quads contribute 4 edges and triangles 3 before deduplication.
+pub fn unique_edges<P: EmitLines<uint> + Clone>(polygons: &[P]) -> Vec<(uint, uint)> {
+    polygons.iter()
+        .map(|p| p.clone())
+        .unique_lines()
+        .map(|l| (cmp::min(l.a, l.b), cmp::max(l.a, l.b)))
+        .collect()
+}
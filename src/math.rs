@@ -0,0 +1,55 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+//! Small vector helpers over `(f32, f32, f32)` tuples used internally.
+
+use std::num::Float;
+
+pub type Vector3 = (f32, f32, f32);
+
+pub fn add((ax, ay, az): Vector3, (bx, by, bz): Vector3) -> Vector3 {
+    (ax + bx, ay + by, az + bz)
+}
+
+pub fn sub((ax, ay, az): Vector3, (bx, by, bz): Vector3) -> Vector3 {
+    (ax - bx, ay - by, az - bz)
+}
+
+pub fn scale((x, y, z): Vector3, s: f32) -> Vector3 {
+    (x * s, y * s, z * s)
+}
+
+pub fn dot((ax, ay, az): Vector3, (bx, by, bz): Vector3) -> f32 {
+    ax * bx + ay * by + az * bz
+}
+
+pub fn cross((ax, ay, az): Vector3, (bx, by, bz): Vector3) -> Vector3 {
+    (ay * bz - az * by,
+     az * bx - ax * bz,
+     ax * by - ay * bx)
+}
+
+pub fn length(v: Vector3) -> f32 {
+    dot(v, v).sqrt()
+}
+
+/// normalize `v`, a zero length vector is returned as `(0, 0, 0)`
+pub fn normalize(v: Vector3) -> Vector3 {
+    let len = length(v);
+    if len == 0. {
+        (0., 0., 0.)
+    } else {
+        scale(v, 1. / len)
+    }
+}
@@ -0,0 +1,201 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::f32::consts::PI;
+use std::num::{Float, FloatMath};
+
+use Polygon::{PolyTri, PolyQuad};
+use {Quad, Triangle, Polygon, EmitVertices, Vertices};
+use adapter::FoldPolygons;
+use math::{Vector3, add, sub, scale, dot, cross, length};
+
+/// Calculate the center of a polygon, this is the average of its vertices.
+pub trait Centroid {
+    /// the arithmetic mean of each vertex in the polygon
+    fn centroid(&self) -> Vector3;
+}
+
+impl Centroid for Triangle<Vector3> {
+    fn centroid(&self) -> Vector3 {
+        let &Triangle{x, y, z} = self;
+        scale(add(add(x, y), z), 1. / 3.)
+    }
+}
+
+impl Centroid for Quad<Vector3> {
+    fn centroid(&self) -> Vector3 {
+        let &Quad{x, y, z, w} = self;
+        scale(add(add(x, y), add(z, w)), 0.25)
+    }
+}
+
+impl Centroid for Polygon<Vector3> {
+    fn centroid(&self) -> Vector3 {
+        match self {
+            &PolyTri(ref t) => t.centroid(),
+            &PolyQuad(ref q) => q.centroid()
+        }
+    }
+}
+
+/// Calculate the surface area of a polygon.
+pub trait Area {
+    /// the area of the polygon, a degenerate polygon has an area of 0
+    fn area(&self) -> f32;
+}
+
+impl Area for Triangle<Vector3> {
+    fn area(&self) -> f32 {
+        let &Triangle{x, y, z} = self;
+        length(cross(sub(y, x), sub(z, x))) * 0.5
+    }
+}
+
+impl Area for Quad<Vector3> {
+    /// the sum of the triangles `(x, y, z)` and `(x, z, w)`, which is
+    /// how `Quad::triangulate` splits the quad
+    fn area(&self) -> f32 {
+        let (a, b) = self.clone().triangulate();
+        a.area() + b.area()
+    }
+}
+
+impl Area for Polygon<Vector3> {
+    fn area(&self) -> f32 {
+        match self {
+            &PolyTri(ref t) => t.area(),
+            &PolyQuad(ref q) => q.area()
+        }
+    }
+}
+
+/// Find the axis aligned box that contains every position in a stream,
+/// returned as the `(min, max)` corners. This can be used with the
+/// `Vertices` adapter to measure a stream of polygons.
+///
+/// An empty stream has no bounding box and returns `None`.
+pub fn bounding_box<SRC: Iterator<Vector3>>(mut src: SRC) -> Option<(Vector3, Vector3)> {
+    let first = match src.next() {
+        Some(v) => v,
+        None => return None
+    };
+
+    let ((mut min_x, mut min_y, mut min_z), (mut max_x, mut max_y, mut max_z)) = (first, first);
+    for (x, y, z) in src {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        min_z = min_z.min(z);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+        max_z = max_z.max(z);
+    }
+
+    Some(((min_x, min_y, min_z), (max_x, max_y, max_z)))
+}
+
+/// Measure a whole stream of polygons at once.
+pub trait MeasurePolygons<P> {
+    /// the sum of the area of every polygon
+    fn total_area(self) -> f32;
+}
+
+impl<P: Area, T: Iterator<P>> MeasurePolygons<P> for T {
+    fn total_area(self) -> f32 {
+        self.fold_polygons(0., |sum, p| sum + p.area())
+    }
+}
+
+/// Find a sphere around a whole stream of primitives. Unlike
+/// `MeasurePolygons` this only needs the vertices, so it works on lines
+/// as well as polygons.
+pub trait BoundingSphere<P> {
+    /// a sphere that contains every vertex, returned as its `(center,
+    /// radius)`. The sphere is centered on the bounding box, so it is not
+    /// always the smallest sphere. An empty stream returns `None`.
+    fn bounding_sphere(self) -> Option<(Vector3, f32)>;
+}
+
+impl<P: EmitVertices<Vector3>, T: Iterator<P>> BoundingSphere<P> for T {
+    fn bounding_sphere(self) -> Option<(Vector3, f32)> {
+        let vertices: Vec<Vector3> = self.vertices().collect();
+        bounding_box(vertices.iter().map(|&v| v)).map(|(min, max)| {
+            let center = scale(add(min, max), 0.5);
+            let radius = vertices.iter().fold(0f32, |r, &v| r.max(length(sub(v, center))));
+            (center, radius)
+        })
+    }
+}
+
+/// The worst case quality of a stream of triangles, see `triangle_quality`.
+#[deriving(Copy, Clone, Show, PartialEq)]
+pub struct TriangleQuality {
+    /// the number of triangles that were measured
+    pub triangles: uint,
+    /// the smallest interior angle of any triangle, in radians
+    pub min_angle: f32,
+    /// the largest aspect ratio of any triangle, an equilateral triangle
+    /// has an aspect ratio of 1 and slivers have large aspect ratios
+    pub max_aspect_ratio: f32,
+    /// the number of triangles with next to no area
+    pub degenerate: uint
+}
+
+// the angle between `a` and `b`, atan2 stays in range where acos of a
+// rounded dot product would not
+fn angle(a: Vector3, b: Vector3) -> f32 {
+    length(cross(a, b)).atan2(dot(a, b))
+}
+
+/// Measure the quality of a stream of triangles, this can be used to
+/// find sliver triangles before they cause shading problems.
+///
+/// A triangle is degenerate if its area is tiny compared to its longest
+/// edge. Degenerate triangles are only counted, they are left out of
+/// `min_angle` and `max_aspect_ratio`. If there are no other triangles
+/// `min_angle` is `PI` and `max_aspect_ratio` is 1.
+pub fn triangle_quality<SRC: Iterator<Triangle<Vector3>>>(src: SRC) -> TriangleQuality {
+    let mut quality = TriangleQuality {
+        triangles: 0,
+        min_angle: PI,
+        max_aspect_ratio: 1.,
+        degenerate: 0
+    };
+
+    for Triangle{x, y, z} in src {
+        quality.triangles += 1;
+
+        let (xy, yz, zx) = (sub(y, x), sub(z, y), sub(x, z));
+        let (a, b, c) = (length(xy), length(yz), length(zx));
+        let longest = a.max(b).max(c);
+        let area = length(cross(xy, sub(z, x))) / 2.;
+        if area <= 1e-6 * longest * longest {
+            quality.degenerate += 1;
+            continue;
+        }
+
+        let angles = [angle(xy, scale(zx, -1.)),
+                      angle(yz, scale(xy, -1.)),
+                      angle(zx, scale(yz, -1.))];
+        for &t in angles.iter() {
+            quality.min_angle = quality.min_angle.min(t);
+        }
+
+        // the longest edge over the diameter of the inscribed circle,
+        // scaled so an equilateral triangle is 1
+        let ratio = longest * (a + b + c) / (4. * 3f32.sqrt() * area);
+        quality.max_aspect_ratio = quality.max_aspect_ratio.max(ratio);
+    }
+
+    quality
+}
@@ -0,0 +1,92 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use {Quad, Triangle, Polygon};
+use Polygon::{PolyTri, PolyQuad};
+use math::{Vector3, sub, dot, cross};
+
+// the directed edges of a triangle, each paired with the vertex across
+// from it
+fn edges(t: &Triangle<uint>) -> [(uint, uint, uint), ..3] {
+    [(t.x, t.y, t.z), (t.y, t.z, t.x), (t.z, t.x, t.y)]
+}
+
+/// Merge pairs of neighboring triangles that lie in the same plane into
+/// quads, such as when reading back a mesh that was triangulated on
+/// export. Two triangles are merged if they share an edge in opposite
+/// directions and the far vertex of each is within `eps` of the plane of
+/// the other. Each triangle is merged at most once, trying its longest
+/// edges first, and triangles that can not be merged are kept as they
+/// are.
+///
+/// The shared edge becomes the `x`-`z` diagonal of the quad, so
+/// triangulating the quad gives back the two triangles it was made from.
+pub fn merge_coplanar(vertices: &[Vector3],
+                      triangles: &[Triangle<uint>],
+                      eps: f32) -> Vec<Polygon<uint>> {
+    let mut directed: HashMap<(uint, uint), uint> = HashMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        for &(a, b, _) in edges(t).iter() {
+            directed.insert((a, b), i);
+        }
+    }
+
+    let planar = |a: uint, b: uint, c: uint, d: uint| {
+        let q = Quad::new(vertices[a], vertices[b], vertices[c], vertices[d]);
+        let (x, y, z) = cross(sub(q.y, q.x), sub(q.z, q.x));
+        (x != 0. || y != 0. || z != 0.) && q.is_planar(eps)
+    };
+
+    let mut merged = Vec::from_elem(triangles.len(), false);
+    let mut out = Vec::with_capacity(triangles.len());
+    for (i, t) in triangles.iter().enumerate() {
+        if merged[i] {
+            continue;
+        }
+        merged[i] = true;
+
+        // try the longest edges first
+        let mut sides: Vec<(f32, (uint, uint, uint))> = edges(t).iter().map(|&(a, b, c)| {
+            let d = sub(vertices[b], vertices[a]);
+            (dot(d, d), (a, b, c))
+        }).collect();
+        sides.sort_by(|&(a, _), &(b, _)| b.partial_cmp(&a).unwrap_or(Ordering::Equal));
+
+        let mut quad = None;
+        for &(_, (p, q, far)) in sides.iter() {
+            let j = match directed.get(&(q, p)) {
+                Some(&j) if !merged[j] => j,
+                _ => continue
+            };
+            let other = edges(&triangles[j]).iter()
+                .find(|&&(a, b, _)| (a, b) == (q, p))
+                .map(|&(_, _, c)| c)
+                .unwrap();
+            if planar(q, far, p, other) && planar(p, other, q, far) {
+                merged[j] = true;
+                quad = Some(Quad::new(q, far, p, other));
+                break;
+            }
+        }
+
+        out.push(match quad {
+            Some(q) => PolyQuad(q),
+            None => PolyTri(*t)
+        });
+    }
+    out
+}
@@ -0,0 +1,111 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::f32::consts::PI_2;
+use std::num::FloatMath;
+use super::{Quad, Polygon, MapVertex};
+use super::Polygon::PolyQuad;
+use super::generators::{SharedVertex, IndexedPolygon};
+
+/// A Mobius strip lying around the z axis and centered at (0, 0, 0). The
+/// strip makes a half twist on its way around, so the end of the strip
+/// joins its start upside down and it only has one side.
+///
+/// Since the strip has no front or back there is no winding that agrees
+/// everywhere, the polygons on either side of the join walk their shared
+/// edge in the same direction. This makes it a useful test for code that
+/// checks or repairs windings.
+#[deriving(Copy)]
+pub struct Mobius {
+    idx: uint,
+    radius: f32,
+    width: f32,
+    segments: uint
+}
+
+impl Mobius {
+    /// Create a new Mobius strip.
+    /// `radius` is the distance from the center to the middle of the strip.
+    /// `width` is the distance across the strip.
+    /// `segments` is the number of quads around the strip.
+    pub fn new(radius: f32, width: f32, segments: uint) -> Mobius {
+        assert!(segments > 2);
+        Mobius {
+            idx: 0,
+            radius: radius,
+            width: width,
+            segments: segments
+        }
+    }
+
+    // the point on edge `side` of the strip at segment `i`
+    fn vert(&self, i: uint, side: uint) -> (f32, f32, f32) {
+        let a = (i as f32 / self.segments as f32) * PI_2;
+        let s = self.width * if side == 0 { -0.5 } else { 0.5 };
+        // the strip turns half as fast as it goes around
+        let (ts, tc) = (a * 0.5).sin_cos();
+        let r = self.radius + s * tc;
+        (r * a.cos(), r * a.sin(), s * ts)
+    }
+
+    // the shared index of edge `side` at segment `i`, after a full turn
+    // the two edges have swapped places
+    fn index(&self, i: uint, side: uint) -> uint {
+        if i == self.segments {
+            1 - side
+        } else {
+            2 * i + side
+        }
+    }
+}
+
+impl Iterator<Polygon<(f32, f32, f32)>> for Mobius {
+    fn next(&mut self) -> Option<Polygon<(f32, f32, f32)>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl SharedVertex<(f32, f32, f32)> for Mobius {
+    fn shared_vertex(&self, idx: uint) -> (f32, f32, f32) {
+        self.vert(idx / 2, idx % 2)
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        2 * self.segments
+    }
+}
+
+impl IndexedPolygon<Polygon<uint>> for Mobius {
+    fn indexed_polygon(&self, i: uint) -> Polygon<uint> {
+        PolyQuad(Quad::new(self.index(i,   0),
+                           self.index(i+1, 0),
+                           self.index(i+1, 1),
+                           self.index(i,   1)))
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        self.segments
+    }
+}
@@ -0,0 +1,157 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::collections::HashMap;
+
+use {Polygon, MapVertex, FaceNormal};
+use poly::EmitVertices;
+use math::{Vector3, add, normalize};
+
+/// `Neighbors` is a lookup structure for an indexed mesh. It records
+/// which polygons share each vertex and each edge so that adjacent faces
+/// can be found without searching the entire mesh.
+///
+/// Edges are undirected, the edge `(a, b)` is the same edge as `(b, a)`.
+pub struct Neighbors<T> {
+    vertices: Vec<T>,
+    polygons: Vec<Polygon<uint>>,
+    shares_edge: HashMap<(uint, uint), Vec<uint>>,
+    shares_vertex: HashMap<uint, Vec<uint>>
+}
+
+/// order an edge so that `(a, b)` and `(b, a)` have the same key
+fn edge_key(a: uint, b: uint) -> (uint, uint) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+/// collect the edges of a polygon in winding order
+pub fn polygon_edges(p: &Polygon<uint>) -> Vec<(uint, uint)> {
+    let mut verts = Vec::new();
+    p.clone().emit_vertices(|v| verts.push(v));
+
+    let mut edges = Vec::with_capacity(verts.len());
+    for i in range(0, verts.len()) {
+        edges.push((verts[i], verts[(i + 1) % verts.len()]));
+    }
+    edges
+}
+
+impl<T> Neighbors<T> {
+    /// build the neighbor lookup for a mesh from its shared vertices and
+    /// indexed polygons.
+    pub fn new(vertices: Vec<T>, polygons: Vec<Polygon<uint>>) -> Neighbors<T> {
+        let mut shares_edge = HashMap::new();
+        let mut shares_vertex = HashMap::new();
+
+        for (i, p) in polygons.iter().enumerate() {
+            for &(a, b) in polygon_edges(p).iter() {
+                if a == b {
+                    continue;
+                }
+
+                let key = edge_key(a, b);
+                if !shares_edge.contains_key(&key) {
+                    shares_edge.insert(key, Vec::new());
+                }
+                let faces = shares_edge.get_mut(&key).unwrap();
+                if !faces.contains(&i) {
+                    faces.push(i);
+                }
+
+                if !shares_vertex.contains_key(&a) {
+                    shares_vertex.insert(a, Vec::new());
+                }
+                let faces = shares_vertex.get_mut(&a).unwrap();
+                if !faces.contains(&i) {
+                    faces.push(i);
+                }
+            }
+        }
+
+        Neighbors {
+            vertices: vertices,
+            polygons: polygons,
+            shares_edge: shares_edge,
+            shares_vertex: shares_vertex
+        }
+    }
+
+    /// the polygons of the mesh
+    pub fn polygons(&self) -> &[Polygon<uint>] {
+        self.polygons.as_slice()
+    }
+
+    /// the shared vertices of the mesh
+    pub fn vertices(&self) -> &[T] {
+        self.vertices.as_slice()
+    }
+
+    /// return the polygons that use the vertex `v`
+    pub fn vertex_polygons(&self, v: uint) -> Option<&[uint]> {
+        self.shares_vertex.get(&v).map(|x| x.as_slice())
+    }
+
+    /// return the polygons that contain the edge between `a` and `b`.
+    /// A boundary edge only has a single polygon.
+    pub fn edge_polygons(&self, a: uint, b: uint) -> Option<&[uint]> {
+        self.shares_edge.get(&edge_key(a, b)).map(|x| x.as_slice())
+    }
+
+    /// return the polygons that share an edge with polygon `face`. A polygon
+    /// that lies entirely on the boundary of the mesh will have no neighbors.
+    /// `None` is returned if `face` is not part of the mesh.
+    pub fn neighbors(&self, face: uint) -> Option<Vec<uint>> {
+        if face >= self.polygons.len() {
+            return None;
+        }
+
+        let mut out = Vec::new();
+        for &(a, b) in polygon_edges(&self.polygons[face]).iter() {
+            match self.edge_polygons(a, b) {
+                Some(faces) => {
+                    for &f in faces.iter() {
+                        if f != face && !out.contains(&f) {
+                            out.push(f);
+                        }
+                    }
+                }
+                None => ()
+            }
+        }
+        Some(out)
+    }
+
+    /// calculate the face normal of every polygon that uses the vertex `v`.
+    /// `f` is used to convert a vertex to its position.
+    pub fn normals_for_vertex(&self, v: uint, f: |&T| -> Vector3) -> Vec<Vector3> {
+        let faces = match self.vertex_polygons(v) {
+            Some(faces) => faces,
+            None => return Vec::new()
+        };
+
+        let mut normals = Vec::with_capacity(faces.len());
+        for &i in faces.iter() {
+            let p = self.polygons[i].clone().map_vertex(|idx| f(&self.vertices[idx]));
+            normals.push(p.face_normal());
+        }
+        normals
+    }
+
+    /// calculate the smooth normal of vertex `v` by averaging the face normals
+    /// of every polygon that uses it.
+    pub fn normal_for_vertex(&self, v: uint, f: |&T| -> Vector3) -> Vector3 {
+        let normals = self.normals_for_vertex(v, f);
+        normalize(normals.into_iter().fold((0., 0., 0.), |acc, n| add(acc, n)))
+    }
+}
@@ -0,0 +1,219 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::collections::HashMap;
+use std::num::FloatMath;
+
+use Polygon::{PolyTri, PolyQuad};
+use {Quad, Triangle, Polygon, MapVertex, EmitVertices, EmitVerticesRef};
+use generators::{SharedVertex, IndexedPolygon};
+use math::{Vector3, add, sub, scale, dot, cross, normalize};
+
+/// Calculate the flat normal of a polygon. The normal follows the
+/// winding of the polygon, a counter clockwise polygon will have its
+/// normal pointing towards the viewer.
+pub trait FaceNormal<P> {
+    /// calculate the normalized face normal. A degenerate polygon
+    /// with no area has a normal of `(0, 0, 0)`
+    fn face_normal(&self) -> Vector3;
+
+    /// pair each vertex of the polygon with the face normal, the result
+    /// is a polygon of `(position, normal)`
+    fn with_face_normal(self) -> P;
+}
+
+impl FaceNormal<Triangle<(Vector3, Vector3)>> for Triangle<Vector3> {
+    fn face_normal(&self) -> Vector3 {
+        let &Triangle{x, y, z} = self;
+        normalize(cross(sub(y, x), sub(z, x)))
+    }
+
+    fn with_face_normal(self) -> Triangle<(Vector3, Vector3)> {
+        let n = self.face_normal();
+        self.map_vertex(|v| (v, n))
+    }
+}
+
+impl FaceNormal<Quad<(Vector3, Vector3)>> for Quad<Vector3> {
+    fn face_normal(&self) -> Vector3 {
+        let &Quad{x, y, z: _, w} = self;
+        normalize(cross(sub(y, x), sub(w, x)))
+    }
+
+    fn with_face_normal(self) -> Quad<(Vector3, Vector3)> {
+        let n = self.face_normal();
+        self.map_vertex(|v| (v, n))
+    }
+}
+
+impl FaceNormal<Polygon<(Vector3, Vector3)>> for Polygon<Vector3> {
+    fn face_normal(&self) -> Vector3 {
+        match self {
+            &PolyTri(ref t) => t.face_normal(),
+            &PolyQuad(ref q) => q.face_normal()
+        }
+    }
+
+    fn with_face_normal(self) -> Polygon<(Vector3, Vector3)> {
+        match self {
+            PolyTri(t) => PolyTri(t.with_face_normal()),
+            PolyQuad(q) => PolyQuad(q.with_face_normal())
+        }
+    }
+}
+
+/// Calculate a smooth normal for every shared vertex of a mesh. The
+/// result is parallel to the shared vertices, the normal of shared
+/// vertex `i` is at offset `i`.
+///
+/// Each vertex normal is the sum of the normals of the polygons that use
+/// the vertex, weighted by the area of each polygon. Polygons with no
+/// area do not contribute, a vertex that is only used by degenerate
+/// polygons has a normal of `(0, 0, 0)`.
+pub fn generate_normals<P: EmitVertices<uint>,
+                        G: SharedVertex<Vector3> + IndexedPolygon<P>>
+                       (generator: &G) -> Vec<Vector3> {
+    let mut normals = Vec::from_elem(generator.shared_vertex_count(), (0., 0., 0.));
+
+    for p in generator.indexed_polygon_iter() {
+        let mut indices = Vec::new();
+        p.emit_vertices(|i| indices.push(i));
+
+        // Newell's method, the length of the sum is twice the area of
+        // the polygon so larger polygons are weighted more heavily
+        let mut n = (0., 0., 0.);
+        for (j, &a) in indices.iter().enumerate() {
+            let b = indices[(j + 1) % indices.len()];
+            n = add(n, cross(generator.shared_vertex(a),
+                             generator.shared_vertex(b)));
+        }
+
+        if n == (0., 0., 0.) {
+            continue;
+        }
+
+        for &i in indices.iter() {
+            normals[i] = add(normals[i], n);
+        }
+    }
+
+    normals.into_iter().map(|n| normalize(n)).collect()
+}
+
+/// Calculate normals that are smooth across soft edges and keep hard
+/// edges sharp. At each corner of a polygon the normal is the area
+/// weighted sum of the normals of the polygons around the vertex that
+/// are within `crease_angle` radians of the polygon's own normal.
+///
+/// A shared vertex that ends up with more than one normal is split, so
+/// the result is a new list of `(position, normal)` vertices along with
+/// the polygons rewritten to use them. A `crease_angle` of 0 gives every
+/// polygon flat normals, and an angle of `PI` gives the same normals as
+/// `generate_normals`.
+pub fn generate_crease_normals<P: EmitVerticesRef<uint> + MapVertex<uint, uint, P>,
+                               G: SharedVertex<Vector3> + IndexedPolygon<P>>
+                              (generator: &G, crease_angle: f32) -> (Vec<(Vector3, Vector3)>, Vec<P>) {
+    let polygons: Vec<P> = generator.indexed_polygon_iter().collect();
+    let min_cos = crease_angle.cos();
+
+    // the area weighted normal of each polygon and the polygons that use
+    // each shared vertex
+    let mut weighted = Vec::with_capacity(polygons.len());
+    let mut faces: Vec<Vec<uint>> = range(0, generator.shared_vertex_count()).map(|_| Vec::new()).collect();
+    for (f, p) in polygons.iter().enumerate() {
+        let mut indices = Vec::new();
+        p.emit_vertices_ref(|&i| indices.push(i));
+
+        let mut n = (0., 0., 0.);
+        for (j, &a) in indices.iter().enumerate() {
+            let b = indices[(j + 1) % indices.len()];
+            n = add(n, cross(generator.shared_vertex(a),
+                             generator.shared_vertex(b)));
+        }
+        weighted.push(n);
+
+        for &i in indices.iter() {
+            if !faces[i].contains(&f) {
+                faces[i].push(f);
+            }
+        }
+    }
+    let unit: Vec<Vector3> = weighted.iter().map(|&n| normalize(n)).collect();
+
+    // corners that blend the same set of polygons share a vertex
+    let mut split: HashMap<(uint, Vec<uint>), uint> = HashMap::new();
+    let mut vertices = Vec::new();
+    let mut out = Vec::with_capacity(polygons.len());
+    for (f, p) in polygons.into_iter().enumerate() {
+        out.push(p.map_vertex(|i| {
+            // a polygon always blends its own normal, degenerate polygons
+            // have no direction to compare so they are never blended in by
+            // their neighbours
+            let mut blend = vec![f];
+            if unit[f] != (0., 0., 0.) {
+                blend.extend(faces[i].iter()
+                    .map(|&g| g)
+                    .filter(|&g| g != f && unit[g] != (0., 0., 0.))
+                    .filter(|&g| dot(unit[f], unit[g]) >= min_cos));
+                blend.sort();
+            }
+
+            match split.get(&(i, blend.clone())) {
+                Some(&v) => return v,
+                None => ()
+            }
+
+            let n = blend.iter().fold((0., 0., 0.), |n, &g| add(n, weighted[g]));
+            vertices.push((generator.shared_vertex(i), normalize(n)));
+            split.insert((i, blend), vertices.len() - 1);
+            vertices.len() - 1
+        }));
+    }
+
+    (vertices, out)
+}
+
+/// Replace the normal of every `(position, normal)` vertex with a color
+/// to visualize it, each component is mapped from -1 to 1 onto 0 to 1.
+/// A normal facing `+x` is colored `(1, 0.5, 0.5)`.
+pub trait ColorByNormal<SRC> {
+    /// color every vertex by its normal
+    fn color_by_normal(self) -> ColorByNormalIterator<SRC>;
+}
+
+impl<P: MapVertex<(Vector3, Vector3), (Vector3, Vector3), P>, T: Iterator<P>> ColorByNormal<T> for T {
+    fn color_by_normal(self) -> ColorByNormalIterator<T> {
+        ColorByNormalIterator {
+            source: self
+        }
+    }
+}
+
+/// an iterator that colors every vertex by its normal
+pub struct ColorByNormalIterator<SRC> {
+    source: SRC
+}
+
+impl<P: MapVertex<(Vector3, Vector3), (Vector3, Vector3), P>, SRC: Iterator<P>> Iterator<P>
+    for ColorByNormalIterator<SRC> {
+    fn next(&mut self) -> Option<P> {
+        self.source.next().map(|p| p.map_vertex(|(v, n)| {
+            (v, add(scale(n, 0.5), (0.5, 0.5, 0.5)))
+        }))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.source.size_hint()
+    }
+}
@@ -0,0 +1,119 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::io::{Reader, Writer, IoResult, IoError, IoErrorKind};
+
+use {Triangle, Polygon};
+use Polygon::PolyTri;
+use poly::EmitVertices;
+use generators::{SharedVertex, IndexedPolygon};
+
+/// Write a mesh in the Wavefront OBJ format. Each shared vertex is written
+/// as a `v` line followed by an `f` line for every polygon. Quads are
+/// written as four index faces.
+///
+/// OBJ indices start at 1, so every index from the generator is offset
+/// by one when written.
+pub fn to_obj<W: Writer,
+              P: EmitVertices<uint>,
+              G: SharedVertex<(f32, f32, f32)> + IndexedPolygon<P>>
+             (w: &mut W, generator: &G) -> IoResult<()> {
+
+    for (x, y, z) in generator.shared_vertex_iter() {
+        try!(writeln!(w, "v {} {} {}", x, y, z));
+    }
+
+    for p in generator.indexed_polygon_iter() {
+        let mut indices = Vec::new();
+        p.emit_vertices(|i| indices.push(i));
+
+        try!(write!(w, "f"));
+        for i in indices.iter() {
+            try!(write!(w, " {}", i + 1));
+        }
+        try!(writeln!(w, ""));
+    }
+
+    Ok(())
+}
+
+fn invalid(desc: &'static str, line: uint) -> IoError {
+    IoError {
+        kind: IoErrorKind::InvalidInput,
+        desc: desc,
+        detail: Some(format!("on line {}", line))
+    }
+}
+
+/// Read a mesh in the Wavefront OBJ format, returning the positions from
+/// the `v` lines and the polygons from the `f` lines. OBJ indices start
+/// at 1 and are converted to start at 0, negative indices count back
+/// from the last vertex read.
+///
+/// Faces with 3 or 4 vertices become a `PolyTri` or a `PolyQuad`, larger
+/// faces are split into a fan of triangles around their first vertex.
+/// Texture coordinates and normals are skipped, as are any other lines.
+/// A face with fewer than 3 vertices or an index that does not refer to
+/// a vertex is an error.
+pub fn from_obj<R: Reader>(r: &mut R) -> IoResult<(Vec<(f32, f32, f32)>, Vec<Polygon<uint>>)> {
+    let text = try!(r.read_to_string());
+    let mut vertices = Vec::new();
+    let mut polygons = Vec::new();
+
+    for (n, line) in text.as_slice().lines().enumerate() {
+        let line_number = n + 1;
+        let mut words = line.words();
+        match words.next() {
+            Some("v") => {
+                let v: Vec<f32> = words.take(3).filter_map(|w| from_str(w)).collect();
+                if v.len() != 3 {
+                    return Err(invalid("vertex does not have 3 coordinates", line_number));
+                }
+                vertices.push((v[0], v[1], v[2]));
+            }
+            Some("f") => {
+                let mut indices = Vec::new();
+                for w in words {
+                    // only the position of `v/vt/vn` is used
+                    let v = w.split('/').next().unwrap_or("");
+                    let i: int = match from_str(v) {
+                        Some(i) => i,
+                        None => return Err(invalid("face index is not a number", line_number))
+                    };
+                    let i = if i < 0 { vertices.len() as int + i } else { i - 1 };
+                    if i < 0 || i as uint >= vertices.len() {
+                        return Err(invalid("face index is out of range", line_number));
+                    }
+                    indices.push(i as uint);
+                }
+
+                let v = indices.as_slice();
+                if v.len() < 3 {
+                    return Err(invalid("face has fewer than 3 vertices", line_number));
+                }
+                match Polygon::from_slice(v) {
+                    Some(p) => polygons.push(p),
+                    None => {
+                        for i in range(1, v.len() - 1) {
+                            polygons.push(PolyTri(Triangle::new(v[0], v[i], v[i+1])));
+                        }
+                    }
+                }
+            }
+            _ => ()
+        }
+    }
+
+    Ok((vertices, polygons))
+}
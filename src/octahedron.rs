@@ -0,0 +1,77 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use core::iter::Range;
+use super::{MapVertex, Triangle, Polygon};
+use super::Polygon::PolyTri;
+use super::generators::{SharedVertex, IndexedPolygon};
+
+// the top four faces wind around +z, the bottom four around -z
+static FACES: [[uint, ..3], ..8] = [
+    [0, 2, 4], [2, 1, 4], [1, 3, 4], [3, 0, 4],
+    [2, 0, 5], [1, 2, 5], [3, 1, 5], [0, 3, 5]
+];
+
+/// A regular octahedron with a vertex at 1 along each of the positive
+/// and negative axes, centered at (0, 0, 0).
+#[deriving(Copy)]
+pub struct Octahedron {
+    range: Range<uint>
+}
+
+impl Octahedron {
+    /// create a new octahedron generator
+    pub fn new() -> Octahedron {
+        Octahedron { range: range(0, 8) }
+    }
+
+    fn vert(&self, idx: uint) -> (f32, f32, f32) {
+        match idx {
+            0 => ( 1.,  0.,  0.),
+            1 => (-1.,  0.,  0.),
+            2 => ( 0.,  1.,  0.),
+            3 => ( 0., -1.,  0.),
+            4 => ( 0.,  0.,  1.),
+            5 => ( 0.,  0., -1.),
+            idx => panic!("{} vertex is higher then 6", idx)
+        }
+    }
+}
+
+impl Iterator<Polygon<(f32, f32, f32)>> for Octahedron {
+    fn next(&mut self) -> Option<Polygon<(f32, f32, f32)>> {
+        self.range.next().map(|idx| self.indexed_polygon(idx).map_vertex(|i| self.vert(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.range.size_hint()
+    }
+}
+
+impl SharedVertex<(f32, f32, f32)> for Octahedron {
+    fn shared_vertex(&self, idx: uint) -> (f32, f32, f32) {
+        self.vert(idx)
+    }
+
+    fn shared_vertex_count(&self) -> uint { 6 }
+}
+
+impl IndexedPolygon<Polygon<uint>> for Octahedron {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        let f = FACES[idx];
+        PolyTri(Triangle::new(f[0], f[1], f[2]))
+    }
+
+    fn indexed_polygon_count(&self) -> uint { 8 }
+}
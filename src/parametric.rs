@@ -0,0 +1,96 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use super::{Quad, Polygon, MapVertex};
+use super::Polygon::PolyQuad;
+use super::generators::{SharedVertex, IndexedPolygon};
+
+/// A surface defined by a function that maps the unit square `[0, 1]`
+/// by `[0, 1]` to a position. The square is cut into a grid of quads
+/// and the function is evaluated at each corner of the grid.
+///
+/// The function can be a closure, so the surface can depend on values
+/// only known at runtime. It is evaluated again for each shared vertex.
+#[deriving(Copy)]
+pub struct ParametricSurface<F> {
+    idx: uint,
+    sub_u: uint,
+    sub_v: uint,
+    f: F
+}
+
+impl<F: Fn(f32, f32) -> (f32, f32, f32)> ParametricSurface<F> {
+    /// Create a new surface from `f`.
+    /// `u` is the number of quads along the first parameter.
+    /// `v` is the number of quads along the second parameter.
+    pub fn new(u: uint, v: uint, f: F) -> ParametricSurface<F> {
+        assert!(u > 0 && v > 0);
+        ParametricSurface {
+            idx: 0,
+            sub_u: u,
+            sub_v: v,
+            f: f
+        }
+    }
+
+    fn index(&self, u: uint, v: uint) -> uint {
+        v * (self.sub_u + 1) + u
+    }
+}
+
+impl<F: Fn(f32, f32) -> (f32, f32, f32)> Iterator<Polygon<(f32, f32, f32)>> for ParametricSurface<F> {
+    fn next(&mut self) -> Option<Polygon<(f32, f32, f32)>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl<F: Fn(f32, f32) -> (f32, f32, f32)> SharedVertex<(f32, f32, f32)> for ParametricSurface<F> {
+    fn shared_vertex(&self, idx: uint) -> (f32, f32, f32) {
+        let u = idx % (self.sub_u + 1);
+        let v = idx / (self.sub_u + 1);
+        (self.f)(u as f32 / self.sub_u as f32,
+                 v as f32 / self.sub_v as f32)
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        (self.sub_u + 1) * (self.sub_v + 1)
+    }
+}
+
+impl<F: Fn(f32, f32) -> (f32, f32, f32)> IndexedPolygon<Polygon<uint>> for ParametricSurface<F> {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        let u = idx % self.sub_u;
+        let v = idx / self.sub_u;
+
+        PolyQuad(Quad::new(self.index(u,   v),
+                           self.index(u+1, v),
+                           self.index(u+1, v+1),
+                           self.index(u,   v+1)))
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        self.sub_u * self.sub_v
+    }
+}
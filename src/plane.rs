@@ -12,26 +12,44 @@
 //   See the License for the specific language governing permissions and
 //   limitations under the License.
 
-use super::Quad;
-use super::generators::{SharedVertex, IndexedPolygon};
+use super::{Quad, Polygon};
+use super::Polygon::PolyQuad;
+use super::generators::{SharedVertex, IndexedPolygon, TriangleStrip, PRIMITIVE_RESTART};
+
+/// The axes a `Plane` spans, the front of the plane faces the remaining
+/// axis.
+#[deriving(Copy, Clone, Show, PartialEq, Eq)]
+pub enum Orientation {
+    /// spans X and Y and faces +Z, this is the default
+    XY,
+    /// spans X and Z and faces +Y, a ground plane for a y-up world.
+    /// The subdivisions along y run from +Z to -Z.
+    XZ,
+    /// spans Y and Z and faces +X
+    YZ
+}
 
-/// Represents a 2D plane with origin of (0, 0), from 1 to -1
+/// Represents a flat plane on the XY axis with origin of (0, 0, 0),
+/// spanning from -1 to 1 with `z` always 0. See `orientation` to place
+/// it on another pair of axes.
 #[deriving(Copy)]
 pub struct Plane {
     subdivide_x: uint,
     subdivide_y: uint,
     x: uint,
-    y: uint
+    y: uint,
+    orientation: Orientation
 }
 
 impl Plane {
-    /// create a new cube generator
+    /// create a new plane generator, this is a single quad
     pub fn new() -> Plane {
         Plane {
             subdivide_x: 1,
             subdivide_y: 1,
             x: 0,
-            y: 0
+            y: 0,
+            orientation: Orientation::XY
         }
     }
 
@@ -45,27 +63,46 @@ impl Plane {
             subdivide_x: x,
             subdivide_y: y,
             x: 0,
-            y: 0
+            y: 0,
+            orientation: Orientation::XY
+        }
+    }
+
+    /// Choose the axes the plane spans, the default is `Orientation::XY`.
+    /// The polygons are wound counter clockwise seen from the axis the
+    /// plane faces.
+    pub fn orientation(self, orientation: Orientation) -> Plane {
+        Plane {
+            orientation: orientation,
+            ..self
         }
     }
 
-    fn vert(&self, x: uint, y: uint) -> (f32, f32) {
+    fn vert(&self, x: uint, y: uint) -> (f32, f32, f32) {
         let sx = self.subdivide_x as f32;
         let sy = self.subdivide_y as f32;
-        let x = (2. / sx) * x as f32 - 1.;
-        let y = (2. / sy) * y as f32 - 1.;
-        (x, y)
+        // divide last so the edges land exactly on -1 and 1
+        let x = (2. * x as f32) / sx - 1.;
+        let y = (2. * y as f32) / sy - 1.;
+        // each of these is a rotation of the XY plane so the winding
+        // stays counter clockwise from the front
+        match self.orientation {
+            Orientation::XY => (x, y, 0.),
+            Orientation::XZ => (x, 0., -y),
+            Orientation::YZ => (0., x, y)
+        }
     }
 }
 
-impl Iterator<Quad<(f32, f32)>> for Plane {
-    fn next(&mut self) -> Option<Quad<(f32, f32)>> {
+impl Iterator<Polygon<(f32, f32, f32)>> for Plane {
+    fn next(&mut self) -> Option<Polygon<(f32, f32, f32)>> {
         if self.x == self.subdivide_x {
             self.x = 0;
             self.y += 1;
-            if self.y == self.subdivide_y {
-                return None;
-            }
+        }
+
+        if self.y == self.subdivide_y {
+            return None;
         }
 
         let x = self.vert(self.x,   self.y);
@@ -74,12 +111,18 @@ impl Iterator<Quad<(f32, f32)>> for Plane {
         let w = self.vert(self.x,   self.y+1);
         self.x += 1;
 
-        Some(Quad::new(x, y, z, w))
+        Some(PolyQuad(Quad::new(x, y, z, w)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.subdivide_x * self.subdivide_y -
+                (self.y * self.subdivide_x + self.x);
+        (n, Some(n))
     }
 }
 
-impl SharedVertex<(f32, f32)> for Plane {
-    fn shared_vertex(&self, idx: uint) -> (f32, f32) {
+impl SharedVertex<(f32, f32, f32)> for Plane {
+    fn shared_vertex(&self, idx: uint) -> (f32, f32, f32) {
         let y = idx / (self.subdivide_x + 1);
         let x = idx % (self.subdivide_x + 1);
 
@@ -108,6 +151,25 @@ impl IndexedPolygon<Quad<uint>> for Plane {
     }
 }
 
+impl TriangleStrip for Plane {
+    fn triangle_strip(&self) -> Vec<uint> {
+        let w = self.subdivide_x + 1;
+        let mut strip = Vec::with_capacity(self.subdivide_y * (2 * w + 2));
+        for y in range(0, self.subdivide_y) {
+            if y != 0 {
+                strip.push(PRIMITIVE_RESTART);
+            }
+            let (bottom, top) = (y * w, (y + 1) * w);
+            strip.push(bottom);
+            for x in range(0, w) {
+                strip.push(bottom + x);
+                strip.push(top + x);
+            }
+        }
+        strip
+    }
+}
+
 #[test]
 fn test_shared_vertex_count() {
     let plane = Plane::new();
@@ -122,4 +184,16 @@ fn test_shared_vertex_count() {
     let plane = Plane::subdivide(4, 4);
     assert_eq!(plane.shared_vertex_count(), 25)
     assert_eq!(plane.indexed_polygon_count(), 16)
+}
+
+#[test]
+fn test_corners_are_exact() {
+    for &(x, y) in [(1u, 1u), (3, 3), (7, 5), (10, 3)].iter() {
+        let plane = Plane::subdivide(x, y);
+        let last = plane.shared_vertex_count() - 1;
+        assert_eq!(plane.shared_vertex(0), (-1., -1., 0.));
+        assert_eq!(plane.shared_vertex(x), (1., -1., 0.));
+        assert_eq!(plane.shared_vertex(last - x), (-1., 1., 0.));
+        assert_eq!(plane.shared_vertex(last), (1., 1., 0.));
+    }
 }
\ No newline at end of file
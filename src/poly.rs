@@ -12,10 +12,19 @@
 //   See the License for the specific language governing permissions and
 //   limitations under the License.
 
+#[cfg(not(feature = "std"))]
+use core::prelude::*;
+#[cfg(not(feature = "std"))]
+use std::vec::Vec;
 use std::collections::RingBuf;
+use std::num::{Int, Float};
+use std::iter::Enumerate;
+
+use math::{Vector3, sub, dot, cross, normalize};
 
 /// A polygon with 4 points. Maps to `GL_QUADS`
-#[deriving(Clone, Show, PartialEq, Eq, Copy)]
+#[deriving(Clone, Show, PartialEq, Eq, Hash, Copy)]
+#[cfg_attr(feature = "serialize", deriving(Encodable, Decodable))]
 pub struct Quad<T> {
     /// the first point of a quad
     pub x: T,
@@ -39,8 +48,29 @@ impl<T> Quad<T> {
     }
 }
 
+impl<T: Clone> Quad<T> {
+    /// split the quad along its `x`-`z` diagonal into the
+    /// triangles `(x, y, z)` and `(x, z, w)`
+    pub fn triangulate(self) -> (Triangle<T>, Triangle<T>) {
+        let Quad{x, y, z, w} = self;
+        (Triangle::new(x.clone(), y, z.clone()),
+         Triangle::new(x, z, w))
+    }
+}
+
+impl Quad<Vector3> {
+    /// check if the fourth point of the quad is within `eps` of the
+    /// plane through the first three. A quad whose first three points
+    /// are on a line has no plane and is always planar.
+    pub fn is_planar(&self, eps: f32) -> bool {
+        let n = normalize(cross(sub(self.y, self.x), sub(self.z, self.x)));
+        dot(n, sub(self.w, self.x)).abs() <= eps
+    }
+}
+
 /// A polygon with 3 points. Maps to `GL_TRIANGLE`
-#[deriving(Clone, Show, PartialEq, Eq, Copy)]
+#[deriving(Clone, Show, PartialEq, Eq, Hash, Copy)]
+#[cfg_attr(feature = "serialize", deriving(Encodable, Decodable))]
 pub struct Triangle<T> {
     /// the first point of a triangle
     pub x: T,
@@ -61,9 +91,33 @@ impl<T> Triangle<T> {
     }
 }
 
+/// A polygon with any number of points, at least 3. The points are
+/// expected to form a flat convex polygon.
+#[deriving(Clone, Show, PartialEq, Eq, Hash)]
+pub struct NGon<T> {
+    /// the points of the polygon in winding order
+    pub vertices: Vec<T>
+}
+
+impl<T> NGon<T> {
+    /// create a new `NGon` with supplied vertices
+    pub fn new(vertices: Vec<T>) -> NGon<T> {
+        assert!(vertices.len() >= 3);
+        NGon {
+            vertices: vertices
+        }
+    }
+}
+
 /// This is All-the-types container. This exists since some generators
 /// produce both `Triangles` and `Quads`.
-#[deriving(Show, Clone, PartialEq, Copy)]
+///
+/// Polygons are `Eq` and `Hash` when their vertices are, which is the
+/// case for indexed polygons but not for polygons of `f32` positions.
+/// This allows indexed polygons to be stored in a `HashSet` or used as
+/// the key of a `HashMap`.
+#[deriving(Show, Clone, PartialEq, Eq, Hash, Copy)]
+#[cfg_attr(feature = "serialize", deriving(Encodable, Decodable))]
 pub enum Polygon<T> {
     /// A wraped triangle
     PolyTri(Triangle<T>),
@@ -71,6 +125,80 @@ pub enum Polygon<T> {
     PolyQuad(Quad<T>)
 }
 
+impl<T> Polygon<T> {
+    /// Classify a list of vertices by its length, 3 vertices make a
+    /// `PolyTri` and 4 make a `PolyQuad`. Any other length has no
+    /// matching polygon and returns `None`.
+    pub fn from_vec(vertices: Vec<T>) -> Option<Polygon<T>> {
+        let n = vertices.len();
+        let mut v = vertices.into_iter();
+        let mut next = || v.next().unwrap();
+        match n {
+            3 => Some(Polygon::PolyTri(Triangle::new(next(), next(), next()))),
+            4 => Some(Polygon::PolyQuad(Quad::new(next(), next(), next(), next()))),
+            _ => None
+        }
+    }
+}
+
+impl<T: Clone> Polygon<T> {
+    /// The same as `from_vec` but the vertices are cloned out of a slice.
+    pub fn from_slice(v: &[T]) -> Option<Polygon<T>> {
+        match v.len() {
+            3 => Some(Polygon::PolyTri(Triangle::new(v[0].clone(), v[1].clone(), v[2].clone()))),
+            4 => Some(Polygon::PolyQuad(Quad::new(v[0].clone(), v[1].clone(),
+                                                  v[2].clone(), v[3].clone()))),
+            _ => None
+        }
+    }
+}
+
+/// A polygon carrying a value for the whole polygon, such as a material
+/// id or a smoothing group. The vertex adapters map the vertices of the
+/// polygon and pass the tag through untouched, so it survives a pipeline
+/// of `vertex`, `translate` and the like.
+#[deriving(Clone, Show, PartialEq, Eq, Hash, Copy)]
+pub struct Tagged<P, M> {
+    /// the polygon
+    pub polygon: P,
+    /// the value attached to the polygon
+    pub tag: M
+}
+
+impl<P, M> Tagged<P, M> {
+    /// attach `tag` to `polygon`
+    pub fn new(polygon: P, tag: M) -> Tagged<P, M> {
+        Tagged {
+            polygon: polygon,
+            tag: tag
+        }
+    }
+}
+
+/// Lift a `Triangle` or a `Quad` into the `Polygon` enum.
+pub trait IntoPolygon<T> {
+    /// convert into a `Polygon`
+    fn into_polygon(self) -> Polygon<T>;
+}
+
+impl<T> IntoPolygon<T> for Triangle<T> {
+    fn into_polygon(self) -> Polygon<T> {
+        Polygon::PolyTri(self)
+    }
+}
+
+impl<T> IntoPolygon<T> for Quad<T> {
+    fn into_polygon(self) -> Polygon<T> {
+        Polygon::PolyQuad(self)
+    }
+}
+
+impl<T> IntoPolygon<T> for Polygon<T> {
+    fn into_polygon(self) -> Polygon<T> {
+        self
+    }
+}
+
 /// The core mechanism of `Vertices` trait. This is a mechanism for unwraping
 /// a polygon extracting all of the vertices that it bound together.
 pub trait EmitVertices<T> {
@@ -80,6 +208,14 @@ pub trait EmitVertices<T> {
     fn emit_vertices(self, emit: |T|);
 }
 
+/// Borrow the vertices of a polygon without consuming it, the
+/// counterpart of `EmitVertices` for polygons that are only borrowed.
+pub trait EmitVerticesRef<T> {
+    /// Borrow each vertex of the polygon in the same order as
+    /// `emit_vertices`, leaving the polygon untouched
+    fn emit_vertices_ref(&self, emit: |&T|);
+}
+
 impl<T> EmitVertices<T> for Triangle<T> {
     fn emit_vertices(self, emit: |T|) {
         let Triangle{x, y, z} = self;
@@ -89,6 +225,14 @@ impl<T> EmitVertices<T> for Triangle<T> {
     }
 }
 
+impl<T> EmitVerticesRef<T> for Triangle<T> {
+    fn emit_vertices_ref(&self, emit: |&T|) {
+        emit(&self.x);
+        emit(&self.y);
+        emit(&self.z);
+    }
+}
+
 impl<T> EmitVertices<T> for Quad<T> {
     fn emit_vertices(self, emit: |T|) {
         let Quad{x, y, z, w} = self;
@@ -99,6 +243,15 @@ impl<T> EmitVertices<T> for Quad<T> {
     }
 }
 
+impl<T> EmitVerticesRef<T> for Quad<T> {
+    fn emit_vertices_ref(&self, emit: |&T|) {
+        emit(&self.x);
+        emit(&self.y);
+        emit(&self.z);
+        emit(&self.w);
+    }
+}
+
 impl<T> EmitVertices<T> for Polygon<T> {
     fn emit_vertices(self, emit: |T|) {
         use self::Polygon::{ PolyQuad, PolyTri };
@@ -110,6 +263,78 @@ impl<T> EmitVertices<T> for Polygon<T> {
     }
 }
 
+impl<T> EmitVerticesRef<T> for Polygon<T> {
+    fn emit_vertices_ref(&self, emit: |&T|) {
+        use self::Polygon::{ PolyQuad, PolyTri };
+
+        match *self {
+            PolyTri(ref p) => p.emit_vertices_ref(emit),
+            PolyQuad(ref p) => p.emit_vertices_ref(emit)
+        }
+    }
+}
+
+/// Walk the vertices of a single polygon with an `Iterator`, so a polygon
+/// can be used in a `for` loop. The vertices are yielded in the same
+/// order that `EmitVertices` emits them.
+pub trait IntoVertices<T> {
+    /// consume the polygon and produce an iterator over its vertices
+    fn into_vertices(self) -> IntoVerticesIterator<T>;
+}
+
+impl<T, P: EmitVertices<T>> IntoVertices<T> for P {
+    fn into_vertices(self) -> IntoVerticesIterator<T> {
+        let mut buffer = RingBuf::with_capacity(4);
+        self.emit_vertices(|v| buffer.push_back(v));
+        IntoVerticesIterator {
+            buffer: buffer
+        }
+    }
+}
+
+/// an iterator over the vertices of a single polygon
+pub struct IntoVerticesIterator<T> {
+    buffer: RingBuf<T>
+}
+
+impl<T> Iterator<T> for IntoVerticesIterator<T> {
+    fn next(&mut self) -> Option<T> {
+        self.buffer.pop_front()
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        (self.buffer.len(), Some(self.buffer.len()))
+    }
+}
+
+impl<T> EmitVertices<T> for NGon<T> {
+    fn emit_vertices(self, emit: |T|) {
+        for v in self.vertices.into_iter() {
+            emit(v);
+        }
+    }
+}
+
+impl<T> EmitVerticesRef<T> for NGon<T> {
+    fn emit_vertices_ref(&self, emit: |&T|) {
+        for v in self.vertices.iter() {
+            emit(v);
+        }
+    }
+}
+
+impl<T, P: EmitVertices<T>, M> EmitVertices<T> for Tagged<P, M> {
+    fn emit_vertices(self, emit: |T|) {
+        self.polygon.emit_vertices(emit)
+    }
+}
+
+impl<T, P: EmitVerticesRef<T>, M> EmitVerticesRef<T> for Tagged<P, M> {
+    fn emit_vertices_ref(&self, emit: |&T|) {
+        self.polygon.emit_vertices_ref(emit)
+    }
+}
+
 /// Supplies a way to convert an iterator of polygons to an iterator
 /// of vertices. Useful for when you need to write the vertices into
 /// a graphics pipeline.
@@ -122,11 +347,27 @@ impl<V, P: EmitVertices<V>, T: Iterator<P>> Vertices<T, V> for T {
     fn vertices(self) -> VerticesIterator<T, V> {
         VerticesIterator {
             source: self,
-            buffer: RingBuf::new()
+            // large enough to hold a quad without reallocating
+            buffer: RingBuf::with_capacity(4)
         }
     }
 }
 
+/// Like `Vertices`, but each vertex is paired with its position in the
+/// stream. The index counts every vertex that is emitted, it is not the
+/// index of a shared vertex. This is the index a vertex would have when
+/// drawn with `glDrawArrays`.
+pub trait EnumerateVertices<SRC, V> {
+    /// Convert a polygon iterator to an iterator of `(index, vertex)`.
+    fn enumerate_vertices(self) -> Enumerate<VerticesIterator<SRC, V>>;
+}
+
+impl<V, P: EmitVertices<V>, T: Iterator<P>> EnumerateVertices<T, V> for T {
+    fn enumerate_vertices(self) -> Enumerate<VerticesIterator<T, V>> {
+        self.vertices().enumerate()
+    }
+}
+
 /// an iterator that breaks a polygon down into its individual
 /// verticies.
 pub struct VerticesIterator<SRC, V> {
@@ -148,6 +389,66 @@ impl<V, U: EmitVertices<V>, SRC: Iterator<U>> Iterator<V> for VerticesIterator<S
             }
         }
     }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        // every polygon has at least 3 vertices
+        let (n, _) = self.source.size_hint();
+        let n = n.checked_mul(3).and_then(|n| n.checked_add(self.buffer.len()));
+        (n.unwrap_or(Int::max_value()), None)
+    }
+}
+
+/// The same as `Vertices` for a stream that only has triangles, without
+/// the buffer `Vertices` needs to handle polygons of any size. The
+/// vertices come out in the same order as they do from `vertices`.
+pub trait TriangleVertices<SRC, V> {
+    /// Convert a triangle iterator to a vertices iterator.
+    fn triangle_vertices(self) -> TriangleVerticesIterator<SRC, V>;
+}
+
+impl<V, T: Iterator<Triangle<V>>> TriangleVertices<T, V> for T {
+    fn triangle_vertices(self) -> TriangleVerticesIterator<T, V> {
+        TriangleVerticesIterator {
+            source: self,
+            y: None,
+            z: None
+        }
+    }
+}
+
+/// an iterator that breaks a triangle down into its individual vertices
+pub struct TriangleVerticesIterator<SRC, V> {
+    source: SRC,
+    // the vertices of the current triangle that are still to come
+    y: Option<V>,
+    z: Option<V>
+}
+
+impl<V, SRC: Iterator<Triangle<V>>> Iterator<V> for TriangleVerticesIterator<SRC, V> {
+    fn next(&mut self) -> Option<V> {
+        match self.y.take() {
+            Some(v) => return Some(v),
+            None => ()
+        }
+        match self.z.take() {
+            Some(v) => return Some(v),
+            None => ()
+        }
+
+        self.source.next().map(|Triangle{x, y, z}| {
+            self.y = Some(y);
+            self.z = Some(z);
+            x
+        })
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let pending = self.y.iter().count() + self.z.iter().count();
+        let (lower, upper) = self.source.size_hint();
+        let lower = lower.checked_mul(3).and_then(|n| n.checked_add(pending));
+        let upper = upper.and_then(|n| n.checked_mul(3)).and_then(|n| n.checked_add(pending));
+        (lower.unwrap_or(Int::max_value()), upper)
+    }
 }
 
 /// equivalent of `map` but per-vertex
@@ -190,6 +491,80 @@ impl<T: Clone, U> MapVertex<T, U, Polygon<U>> for Polygon<T> {
     }
 }
 
+impl<T, U> MapVertex<T, U, NGon<U>> for NGon<T> {
+    fn map_vertex(self, map: |T| -> U) -> NGon<U> {
+        NGon {
+            vertices: self.vertices.into_iter().map(|v| map(v)).collect()
+        }
+    }
+}
+
+impl<T, U, P: MapVertex<T, U, Q>, Q, M> MapVertex<T, U, Tagged<Q, M>> for Tagged<P, M> {
+    fn map_vertex(self, map: |T| -> U) -> Tagged<Q, M> {
+        let Tagged{polygon, tag} = self;
+        Tagged {
+            polygon: polygon.map_vertex(map),
+            tag: tag
+        }
+    }
+}
+
+/// equivalent of `map_vertex` that borrows the polygon instead of
+/// consuming it, each vertex is passed to the function by reference
+pub trait MapVertexRef<T, U, P> {
+    /// map a function to a reference of each vertex creating a new polygon
+    fn map_vertex_ref(&self, f: |&T| -> U) -> P;
+}
+
+impl<T, U> MapVertexRef<T, U, Triangle<U>> for Triangle<T> {
+    fn map_vertex_ref(&self, map: |&T| -> U) -> Triangle<U> {
+        Triangle {
+            x: map(&self.x),
+            y: map(&self.y),
+            z: map(&self.z)
+        }
+    }
+}
+
+impl<T, U> MapVertexRef<T, U, Quad<U>> for Quad<T> {
+    fn map_vertex_ref(&self, map: |&T| -> U) -> Quad<U> {
+        Quad {
+            x: map(&self.x),
+            y: map(&self.y),
+            z: map(&self.z),
+            w: map(&self.w)
+        }
+    }
+}
+
+impl<T, U> MapVertexRef<T, U, Polygon<U>> for Polygon<T> {
+    fn map_vertex_ref(&self, map: |&T| -> U) -> Polygon<U> {
+        use self::Polygon::{ PolyTri, PolyQuad };
+
+        match *self {
+            PolyTri(ref p) => PolyTri(p.map_vertex_ref(map)),
+            PolyQuad(ref p) => PolyQuad(p.map_vertex_ref(map))
+        }
+    }
+}
+
+impl<T, U> MapVertexRef<T, U, NGon<U>> for NGon<T> {
+    fn map_vertex_ref(&self, map: |&T| -> U) -> NGon<U> {
+        NGon {
+            vertices: self.vertices.iter().map(|v| map(v)).collect()
+        }
+    }
+}
+
+impl<T, U, P: MapVertexRef<T, U, Q>, Q, M: Clone> MapVertexRef<T, U, Tagged<Q, M>> for Tagged<P, M> {
+    fn map_vertex_ref(&self, map: |&T| -> U) -> Tagged<Q, M> {
+        Tagged {
+            polygon: self.polygon.map_vertex_ref(map),
+            tag: self.tag.clone()
+        }
+    }
+}
+
 /// This acts very similar to a vertex shader. It gives a way to manipulate
 /// and modify the vertices in a polygon. This is useful if you need to scale
 /// the mesh using a matrix multiply, or just for modifying the type of each
@@ -221,3 +596,33 @@ impl<'a, POut: MapVertex<T, U, P>,
         self.src.next().map(|x| x.map_vertex(|x| (self.f)(x)))
     }
 }
+
+/// The same as `MapToVertices` but the supplied function only borrows
+/// each vertex, so large vertices do not need to be cloned to be read.
+pub trait MapToVerticesRef<T, U> {
+    /// from a iterator of polygons, produces a iterator of polygons. Each
+    /// vertex is read by reference with the supplied function.
+    fn vertex_ref<'a>(self, map: |&T|:'a -> U) -> MapToVerticesRefIter<'a, Self, T, U>;
+}
+
+impl<VIn, VOut, P, POut: MapVertexRef<VIn, VOut, P>, T: Iterator<POut>>
+    MapToVerticesRef<VIn, VOut> for T {
+    fn vertex_ref<'a>(self, map: |&VIn|:'a -> VOut) -> MapToVerticesRefIter<'a, T, VIn, VOut> {
+        MapToVerticesRefIter {
+            src: self,
+            f: map
+        }
+    }
+}
+
+struct MapToVerticesRefIter<'a, SRC, T, U> {
+    src: SRC,
+    f: |&T|:'a -> U
+}
+
+impl<'a, POut: MapVertexRef<T, U, P>,
+         SRC: Iterator<POut>, T, U, P> Iterator<P> for MapToVerticesRefIter<'a, SRC, T, U> {
+    fn next(&mut self) -> Option<P> {
+        self.src.next().map(|x| x.map_vertex_ref(|x| (self.f)(x)))
+    }
+}
@@ -0,0 +1,50 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use Polygon;
+use poly::{MapVertex, EmitVerticesRef};
+
+/// Rewrite the indices of a list of indexed polygons after the shared
+/// vertices have been reordered. Every index `i` is replaced with
+/// `map[i]`, so `map[i]` must be the new position of the vertex that
+/// used to be at `i`.
+pub fn remap_indices<P: MapVertex<uint, uint, P> + Clone>(polygons: &mut [P], map: &[uint]) {
+    for p in polygons.iter_mut() {
+        *p = p.clone().map_vertex(|i| map[i]);
+    }
+}
+
+/// Drop the shared vertices that no polygon uses, such as after some of
+/// the polygons of a mesh have been filtered out. The vertices that are
+/// kept stay in the same order, and the indices of the polygons are
+/// rewritten to point at their new positions.
+pub fn compact<V: Clone>(vertices: &[V], polygons: &[Polygon<uint>]) -> (Vec<V>, Vec<Polygon<uint>>) {
+    let mut used = Vec::from_elem(vertices.len(), false);
+    for p in polygons.iter() {
+        p.emit_vertices_ref(|&i| used[i] = true);
+    }
+
+    let mut map = Vec::from_elem(vertices.len(), 0);
+    let mut kept = Vec::new();
+    for (i, v) in vertices.iter().enumerate() {
+        if used[i] {
+            map[i] = kept.len();
+            kept.push(v.clone());
+        }
+    }
+
+    let mut polygons = polygons.to_vec();
+    remap_indices(polygons.as_mut_slice(), map.as_slice());
+    (kept, polygons)
+}
@@ -0,0 +1,154 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use super::{Quad, Polygon, MapVertex};
+use super::Polygon::PolyQuad;
+use super::generators::{SharedVertex, IndexedPolygon};
+use math::{Vector3, add, sub, scale, normalize};
+
+// the outward normal of each face followed by the directions `u` and
+// `v` run along it, `u` cross `v` is the normal
+static FACES: [[Vector3, ..3], ..6] = [
+    [( 1.,  0.,  0.), (0., 1., 0.), (0., 0., 1.)],
+    [(-1.,  0.,  0.), (0., 0., 1.), (0., 1., 0.)],
+    [( 0.,  1.,  0.), (0., 0., 1.), (1., 0., 0.)],
+    [( 0., -1.,  0.), (1., 0., 0.), (0., 0., 1.)],
+    [( 0.,  0.,  1.), (1., 0., 0.), (0., 1., 0.)],
+    [( 0.,  0., -1.), (0., 1., 0.), (1., 0., 0.)]
+];
+
+fn clamp(x: f32, limit: f32) -> f32 {
+    if x > limit { limit } else if x < -limit { -limit } else { x }
+}
+
+/// A box centered at (0, 0, 0) with its edges and corners rounded off.
+/// With a `radius` of 0 this is a plain box, and a cube with a `radius`
+/// equal to its half extent is a sphere.
+///
+/// Each face is a grid of quads with its own shared vertices, the
+/// vertices along the edges between faces are not shared.
+#[deriving(Copy)]
+pub struct RoundedBox {
+    idx: uint,
+    half_extents: Vector3,
+    radius: f32,
+    sub: uint
+}
+
+impl RoundedBox {
+    /// Create a new rounded box.
+    /// `half_extents` is the distance from the center to each face.
+    /// `radius` is the radius of the rounded edges, it can not be more
+    /// than the smallest half extent.
+    /// `n` is the number of quads along each side of a face.
+    pub fn new(half_extents: Vector3, radius: f32, n: uint) -> RoundedBox {
+        let (x, y, z) = half_extents;
+        assert!(n > 0);
+        assert!(radius >= 0. && radius <= x && radius <= y && radius <= z);
+        RoundedBox {
+            idx: 0,
+            half_extents: half_extents,
+            radius: radius,
+            sub: n
+        }
+    }
+
+    // the point `(s, t)` of the grid on `face` of the unrounded box
+    fn box_vert(&self, face: uint, s: uint, t: uint) -> Vector3 {
+        let (n, u, v) = (FACES[face][0], FACES[face][1], FACES[face][2]);
+        let s = (2. * s as f32) / self.sub as f32 - 1.;
+        let t = (2. * t as f32) / self.sub as f32 - 1.;
+        let (x, y, z) = add(n, add(scale(u, s), scale(v, t)));
+        let (hx, hy, hz) = self.half_extents;
+        (x * hx, y * hy, z * hz)
+    }
+
+    // the closest point inside the box the rounded corners are around
+    fn inner(&self, (x, y, z): Vector3) -> Vector3 {
+        let (hx, hy, hz) = self.half_extents;
+        let r = self.radius;
+        (clamp(x, hx - r), clamp(y, hy - r), clamp(z, hz - r))
+    }
+
+    fn coords(&self, idx: uint) -> (uint, uint, uint) {
+        let per_face = (self.sub + 1) * (self.sub + 1);
+        let face = idx / per_face;
+        let idx = idx % per_face;
+        (face, idx % (self.sub + 1), idx / (self.sub + 1))
+    }
+
+    /// The exact normal of the shared vertex `idx`. On the flat part of a
+    /// face this is the normal of the face.
+    pub fn shared_normal(&self, idx: uint) -> Vector3 {
+        let (face, s, t) = self.coords(idx);
+        let p = self.box_vert(face, s, t);
+        let n = normalize(sub(p, self.inner(p)));
+        if n == (0., 0., 0.) {
+            FACES[face][0]
+        } else {
+            n
+        }
+    }
+}
+
+impl Iterator<Polygon<Vector3>> for RoundedBox {
+    fn next(&mut self) -> Option<Polygon<Vector3>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl SharedVertex<Vector3> for RoundedBox {
+    fn shared_vertex(&self, idx: uint) -> Vector3 {
+        // the point on the box is pulled in to `radius` from the inner box
+        let (face, s, t) = self.coords(idx);
+        let p = self.box_vert(face, s, t);
+        let inner = self.inner(p);
+        add(inner, scale(normalize(sub(p, inner)), self.radius))
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        6 * (self.sub + 1) * (self.sub + 1)
+    }
+}
+
+impl IndexedPolygon<Polygon<uint>> for RoundedBox {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        let per_face = self.sub * self.sub;
+        let face = idx / per_face;
+        let s = (idx % per_face) % self.sub;
+        let t = (idx % per_face) / self.sub;
+
+        let base = face * (self.sub + 1) * (self.sub + 1);
+        let i = |s: uint, t: uint| base + t * (self.sub + 1) + s;
+        PolyQuad(Quad::new(i(s,   t),
+                           i(s+1, t),
+                           i(s+1, t+1),
+                           i(s,   t+1)))
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        6 * self.sub * self.sub
+    }
+}
@@ -0,0 +1,323 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::num::Float;
+
+use Triangle;
+use math::{Vector3, add, sub, scale, dot, cross, normalize};
+
+// how much more a boundary edge costs to move than an interior edge,
+// this keeps the silhouette of an open mesh in place
+static BOUNDARY_WEIGHT: f64 = 1000.;
+
+// a symmetric 4x4 matrix stored as its upper triangle
+//  0 1 2 3
+//    4 5 6
+//      7 8
+//        9
+type Quadric = [f64, ..10];
+
+// the quadric measuring the squared distance to the plane `n . p + d = 0`
+fn plane_quadric((a, b, c): Vector3, d: f32, weight: f64) -> Quadric {
+    let (a, b, c, d) = (a as f64, b as f64, c as f64, d as f64);
+    [a*a*weight, a*b*weight, a*c*weight, a*d*weight,
+                 b*b*weight, b*c*weight, b*d*weight,
+                             c*c*weight, c*d*weight,
+                                         d*d*weight]
+}
+
+fn add_quadric(q: &mut Quadric, r: &Quadric) {
+    for i in range(0u, 10) {
+        q[i] += r[i];
+    }
+}
+
+fn error(q: &Quadric, (x, y, z): Vector3) -> f64 {
+    let (x, y, z) = (x as f64, y as f64, z as f64);
+    q[0]*x*x + 2.*q[1]*x*y + 2.*q[2]*x*z + 2.*q[3]*x +
+               q[4]*y*y + 2.*q[5]*y*z + 2.*q[6]*y +
+                          q[7]*z*z + 2.*q[8]*z +
+                                     q[9]
+}
+
+// the point with the smallest error, `None` if there is no single best
+// point such as when both ends of the edge lie on the same flat surface
+fn optimal(q: &Quadric) -> Option<Vector3> {
+    let det3 = |a: f64, b: f64, c: f64, d: f64, e: f64, f: f64, g: f64, h: f64, i: f64| {
+        a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g)
+    };
+
+    let det = det3(q[0], q[1], q[2], q[1], q[4], q[5], q[2], q[5], q[7]);
+    if det.abs() < 1e-10 {
+        return None;
+    }
+
+    let (bx, by, bz) = (-q[3], -q[6], -q[8]);
+    let x = det3(bx, q[1], q[2], by, q[4], q[5], bz, q[5], q[7]) / det;
+    let y = det3(q[0], bx, q[2], q[1], by, q[5], q[2], bz, q[7]) / det;
+    let z = det3(q[0], q[1], bx, q[1], q[4], by, q[2], q[5], bz) / det;
+    Some((x as f32, y as f32, z as f32))
+}
+
+// a possible collapse of the edge `a`-`b` to `position`
+struct Candidate {
+    cost: f64,
+    a: uint,
+    b: uint,
+    // the versions of `a` and `b` when this was calculated, if either
+    // vertex has changed since then the candidate is out of date
+    versions: (uint, uint),
+    position: Vector3
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Candidate) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Candidate) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// reversed so that the heap yields the cheapest collapse first
+impl Ord for Candidate {
+    fn cmp(&self, other: &Candidate) -> Ordering {
+        other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
+    }
+}
+
+struct Simplifier {
+    positions: Vec<Vector3>,
+    quadrics: Vec<Quadric>,
+    versions: Vec<uint>,
+    removed: Vec<bool>,
+    // the triangles that use each vertex
+    faces: Vec<Vec<uint>>,
+    triangles: Vec<Option<Triangle<uint>>>,
+    alive: uint
+}
+
+impl Simplifier {
+    fn candidate(&self, a: uint, b: uint) -> Candidate {
+        let mut q = self.quadrics[a];
+        add_quadric(&mut q, &self.quadrics[b]);
+
+        let (pa, pb) = (self.positions[a], self.positions[b]);
+        let mid = scale(add(pa, pb), 0.5);
+        let mut choices = vec![pa, pb, mid];
+        match optimal(&q) {
+            Some(p) => choices.push(p),
+            None => ()
+        }
+
+        let mut best = (error(&q, pa), pa);
+        for &p in choices.iter() {
+            let e = error(&q, p);
+            let (cost, _) = best;
+            if e < cost {
+                best = (e, p);
+            }
+        }
+
+        let (cost, position) = best;
+        Candidate {
+            cost: cost,
+            a: a,
+            b: b,
+            versions: (self.versions[a], self.versions[b]),
+            position: position
+        }
+    }
+
+    fn normal(&self, t: &Triangle<uint>, moved: uint, to: Vector3) -> Vector3 {
+        let p = |i: uint| if i == moved { to } else { self.positions[i] };
+        let (x, y, z) = (p(t.x), p(t.y), p(t.z));
+        normalize(cross(sub(y, x), sub(z, x)))
+    }
+
+    // would moving `v` to `to` turn over any of its triangles that do
+    // not also use `other`
+    fn flips(&self, v: uint, other: uint, to: Vector3) -> bool {
+        for &f in self.faces[v].iter() {
+            let t = self.triangles[f].unwrap();
+            if t.x == other || t.y == other || t.z == other {
+                continue;
+            }
+            let before = self.normal(&t, v, self.positions[v]);
+            let after = self.normal(&t, v, to);
+            if dot(before, after) < 0.2 {
+                return true;
+            }
+        }
+        false
+    }
+
+    // merge `b` into `a`, moving `a` to `position`
+    fn collapse(&mut self, a: uint, b: uint, position: Vector3) {
+        self.positions[a] = position;
+        let qb = self.quadrics[b];
+        add_quadric(&mut self.quadrics[a], &qb);
+        self.removed[b] = true;
+        self.versions[a] += 1;
+
+        let faces = self.faces[b].clone();
+        for &f in faces.iter() {
+            let t = self.triangles[f].unwrap();
+            if t.x == a || t.y == a || t.z == a {
+                // the triangle lies on the collapsed edge and disappears
+                self.triangles[f] = None;
+                self.alive -= 1;
+                for &v in [t.x, t.y, t.z].iter() {
+                    if v != b {
+                        self.faces[v].retain(|&g| g != f);
+                    }
+                }
+            } else {
+                let r = |i: uint| if i == b { a } else { i };
+                self.triangles[f] = Some(Triangle::new(r(t.x), r(t.y), r(t.z)));
+                self.faces[a].push(f);
+            }
+        }
+        self.faces[b].clear();
+    }
+
+    fn neighbors(&self, v: uint) -> Vec<uint> {
+        let mut out = Vec::new();
+        for &f in self.faces[v].iter() {
+            let t = self.triangles[f].unwrap();
+            for &n in [t.x, t.y, t.z].iter() {
+                if n != v && !out.contains(&n) {
+                    out.push(n);
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Reduce the number of triangles in a mesh to at most `target` while
+/// keeping its shape, using quadric error metric edge collapses. The
+/// edge that moves the surface the least is collapsed first. Edges on
+/// the boundary of an open mesh are much more expensive to move, so the
+/// outline of the mesh is kept.
+///
+/// Collapses that would turn a triangle over are skipped, so the target
+/// may not be reached for meshes that cannot be simplified any further.
+/// The returned mesh only has the vertices that are still used.
+pub fn simplify(vertices: &[Vector3],
+                triangles: &[Triangle<uint>],
+                target: uint) -> (Vec<Vector3>, Vec<Triangle<uint>>) {
+    let mut s = Simplifier {
+        positions: vertices.to_vec(),
+        quadrics: Vec::from_elem(vertices.len(), [0., ..10]),
+        versions: Vec::from_elem(vertices.len(), 0),
+        removed: Vec::from_elem(vertices.len(), false),
+        faces: range(0, vertices.len()).map(|_| Vec::new()).collect(),
+        triangles: triangles.iter().map(|&t| Some(t)).collect(),
+        alive: triangles.len()
+    };
+
+    // count how many triangles use each edge to find the boundary
+    let mut edges: HashMap<(uint, uint), uint> = HashMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        for &v in [t.x, t.y, t.z].iter() {
+            s.faces[v].push(i);
+        }
+        for &(a, b) in [(t.x, t.y), (t.y, t.z), (t.z, t.x)].iter() {
+            let key = if a < b { (a, b) } else { (b, a) };
+            let count = match edges.get(&key) { Some(&n) => n, None => 0 };
+            edges.insert(key, count + 1);
+        }
+    }
+
+    for t in triangles.iter() {
+        let (x, y, z) = (vertices[t.x], vertices[t.y], vertices[t.z]);
+        let n = normalize(cross(sub(y, x), sub(z, x)));
+        let q = plane_quadric(n, -dot(n, x), 1.);
+        for &v in [t.x, t.y, t.z].iter() {
+            add_quadric(&mut s.quadrics[v], &q);
+        }
+
+        // a boundary edge gets a plane at right angles to its triangle
+        for &(a, b) in [(t.x, t.y), (t.y, t.z), (t.z, t.x)].iter() {
+            let key = if a < b { (a, b) } else { (b, a) };
+            if edges.get(&key) != Some(&1) {
+                continue;
+            }
+            let side = normalize(cross(sub(vertices[b], vertices[a]), n));
+            let q = plane_quadric(side, -dot(side, vertices[a]), BOUNDARY_WEIGHT);
+            add_quadric(&mut s.quadrics[a], &q);
+            add_quadric(&mut s.quadrics[b], &q);
+        }
+    }
+
+    let mut heap = BinaryHeap::new();
+    for &(a, b) in edges.keys() {
+        if a != b {
+            heap.push(s.candidate(a, b));
+        }
+    }
+
+    while s.alive > target {
+        let c = match heap.pop() {
+            Some(c) => c,
+            None => break
+        };
+
+        let (a, b) = (c.a, c.b);
+        if s.removed[a] || s.removed[b] ||
+           c.versions != (s.versions[a], s.versions[b]) {
+            continue;
+        }
+        if s.flips(a, b, c.position) || s.flips(b, a, c.position) {
+            continue;
+        }
+
+        s.collapse(a, b, c.position);
+        for n in s.neighbors(a).into_iter() {
+            heap.push(s.candidate(a, n));
+        }
+    }
+
+    // drop the vertices that are no longer used
+    let mut remap = Vec::from_elem(vertices.len(), None);
+    let mut out_vertices = Vec::new();
+    let mut out_triangles = Vec::with_capacity(s.alive);
+    for t in s.triangles.iter() {
+        let t = match *t {
+            Some(t) => t,
+            None => continue
+        };
+        let mut index = |v: uint| match remap[v] {
+            Some(i) => i,
+            None => {
+                let i = out_vertices.len();
+                out_vertices.push(s.positions[v]);
+                remap[v] = Some(i);
+                i
+            }
+        };
+        let (x, y, z) = (index(t.x), index(t.y), index(t.z));
+        out_triangles.push(Triangle::new(x, y, z));
+    }
+
+    (out_vertices, out_triangles)
+}
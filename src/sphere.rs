@@ -12,121 +12,410 @@
 //   See the License for the specific language governing permissions and
 //   limitations under the License.
 
-use std::f32::consts::{PI, PI_2};
-use std::num::FloatMath;
-use super::{Quad, Triangle, Polygon};
+use std::num::{Float, FloatMath, cast};
+use std::iter::ExactSizeIterator;
+use std::default::Default;
+use std::fmt;
+use super::{Quad, Triangle, Polygon, MapVertex, ReverseWinding, Winding};
 use super::Polygon::{PolyTri, PolyQuad};
-use super::generators::{SharedVertex, IndexedPolygon};
+use super::generators::{SharedVertex, IndexedPolygon, TriangleStrip, PRIMITIVE_RESTART};
 
-/// Represents a sphere with radius of 1, centered at (0, 0, 0)
+/// How the rows touching the poles of a `SphereUV` are built.
+#[deriving(Copy, Clone, Show, PartialEq)]
+pub enum PoleStyle {
+    /// each pole is a fan of triangles that share the single apex vertex,
+    /// this is the default
+    Triangle,
+    /// the pole rows are quads like the rest of the sphere, with the two
+    /// vertices on the pole collapsed onto the apex
+    Collapsed
+}
+
+/// Represents a sphere, by default with a radius of 1 centered at (0, 0, 0)
+/// with its positions calculated as `T`. `SphereUV` is the `f32` sphere,
+/// a `GenericSphereUV<f64>` has the same shared vertices and polygons but
+/// calculates its positions in double precision.
 #[deriving(Copy)]
-pub struct SphereUV {
+pub struct GenericSphereUV<T> {
     u: uint,
     v: uint,
     sub_u: uint,
-    sub_v: uint
+    sub_v: uint,
+    // one past the last cell that has not been taken from the back
+    back: uint,
+    radius: T,
+    center: (T, T, T),
+    poles: PoleStyle,
+    winding: Winding,
+    u_range: (T, T),
+    v_range: (T, T)
+}
+
+/// A sphere with `f32` positions
+pub type SphereUV = GenericSphereUV<f32>;
+
+// `n` as a `T`, casting a `uint` to a float never fails
+fn float<T: Float>(n: uint) -> T {
+    cast(n).unwrap()
 }
 
 impl SphereUV {
+    /// Create a builder for configuring a sphere by name, starting from
+    /// the same sphere as `SphereUV::default()`.
+    pub fn builder() -> SphereUVBuilder {
+        SphereUVBuilder {
+            sub_u: 32,
+            sub_v: 16,
+            radius: 1.,
+            center: (0., 0., 0.),
+            poles: PoleStyle::Triangle
+        }
+    }
+}
+
+impl<T: Float + FloatMath> GenericSphereUV<T> {
     /// Create a new sphere.
     /// `u` is the number of points across the equator of the sphere.
     /// `v` is the number of points from pole to pole.
-    pub fn new(u: uint, v: uint) -> SphereUV {
-        SphereUV {
+    pub fn new(u: uint, v: uint) -> GenericSphereUV<T> {
+        GenericSphereUV::with_radius(u, v, Float::one())
+    }
+
+    /// Create a new sphere centered at (0, 0, 0) with the supplied `radius`.
+    pub fn with_radius(u: uint, v: uint, radius: T) -> GenericSphereUV<T> {
+        GenericSphereUV::at(u, v, (Float::zero(), Float::zero(), Float::zero()), radius)
+    }
+
+    /// Create a new sphere centered at `center` with the supplied `radius`.
+    pub fn at(u: uint, v: uint, center: (T, T, T), radius: T) -> GenericSphereUV<T> {
+        GenericSphereUV {
             u: 0,
             v: 0,
             sub_u: u,
-            sub_v: v
+            sub_v: v,
+            back: u * v,
+            radius: radius,
+            center: center,
+            poles: PoleStyle::Triangle,
+            winding: Winding::CounterClockwise,
+            u_range: (Float::zero(), Float::one()),
+            v_range: (Float::zero(), Float::one())
+        }
+    }
+
+    /// Create a unit sphere that only covers part of the surface.
+    /// `u_range` is the part of the way around the equator that is
+    /// covered and `v_range` is the part of the way from the top pole to
+    /// the bottom pole, both are fractions from 0 to 1. `u` and `v`
+    /// subdivisions are spread across just the covered part.
+    ///
+    /// The edges of the covered part are left open. A pole is only
+    /// included if the range reaches it, and the seam is only shared if
+    /// `u_range` is `(0, 1)`.
+    pub fn partial(u: uint, v: uint, u_range: (T, T), v_range: (T, T)) -> GenericSphereUV<T> {
+        GenericSphereUV {
+            u_range: u_range,
+            v_range: v_range,
+            ..GenericSphereUV::new(u, v)
         }
     }
 
-    fn vert(&self, u: uint, v: uint) -> (f32, f32, f32) {
-        let u = (u as f32 / self.sub_u as f32) * PI_2;
-        let v = (v as f32 / self.sub_v as f32) * PI;
+    /// Choose how the rows around the poles are built, see `PoleStyle`.
+    pub fn pole_style(self, poles: PoleStyle) -> GenericSphereUV<T> {
+        GenericSphereUV {
+            poles: poles,
+            ..self
+        }
+    }
+
+    /// Choose the winding of the polygons seen from outside the sphere,
+    /// the default is `Winding::CounterClockwise`. This applies to the
+    /// indexed polygons and to the tex coord and normal variants as well.
+    pub fn winding(self, winding: Winding) -> GenericSphereUV<T> {
+        GenericSphereUV {
+            winding: winding,
+            ..self
+        }
+    }
+
+    /// Create a generator for the same sphere that pairs each position
+    /// with a texture coordinate.
+    pub fn with_tex_coords(&self) -> SphereUVTexCoords<T> {
+        SphereUVTexCoords {
+            sphere: *self,
+            idx: 0,
+            centered_poles: false
+        }
+    }
+
+    /// Create a generator for the same sphere that pairs each position
+    /// with its normal. The normals are exact, each is the direction from
+    /// the center of the sphere to the position.
+    pub fn with_normals(&self) -> SphereUVNormals<T> {
+        SphereUVNormals {
+            sphere: *self,
+            idx: 0
+        }
+    }
+
+    // the point on the unit sphere before it is scaled and moved
+    fn unit_vert(&self, u: uint, v: uint) -> (T, T, T) {
+        let (u0, u1) = self.u_range;
+        let (v0, v1) = self.v_range;
+        let (pi, two_pi): (T, T) = (Float::pi(), Float::two_pi());
+        let u = (u0 + (float::<T>(u) / float(self.sub_u)) * (u1 - u0)) * two_pi;
+        let v = (v0 + (float::<T>(v) / float(self.sub_v)) * (v1 - v0)) * pi;
 
         (u.cos() * v.sin(),
          u.sin() * v.sin(),
          v.cos())
     }
+
+    fn vert(&self, u: uint, v: uint) -> (T, T, T) {
+        let (x, y, z) = self.unit_vert(u, v);
+        let (cx, cy, cz) = self.center;
+        (cx + x * self.radius,
+         cy + y * self.radius,
+         cz + z * self.radius)
+    }
+
+    // the row and column of the shared vertex `idx`
+    fn shared_coords(&self, idx: uint) -> (uint, uint) {
+        if idx == 0 && self.top_pole() {
+            (0, 0)
+        } else if idx == self.shared_vertex_count() - 1 && self.bottom_pole() {
+            (0, self.sub_v)
+        } else {
+            // since the pole verts all map to the same
+            // we jump over them in index space
+            let idx = idx - self.first_ring();
+            let u = idx % self.ring_len();
+            let v = idx / self.ring_len();
+            (u, v + self.first_ring())
+        }
+    }
+
+    // the linear index of the next cell taken from the front
+    fn front(&self) -> uint {
+        self.v * self.sub_u + self.u
+    }
+
+    // does the sphere go all the way around, sharing the seam
+    fn wraps(&self) -> bool {
+        let (u0, u1) = self.u_range;
+        u0 == Float::zero() && u1 == Float::one()
+    }
+
+    fn top_pole(&self) -> bool {
+        let (v0, _) = self.v_range;
+        v0 == Float::zero()
+    }
+
+    fn bottom_pole(&self) -> bool {
+        let (_, v1) = self.v_range;
+        v1 == Float::one()
+    }
+
+    // the number of shared vertices in each ring
+    fn ring_len(&self) -> uint {
+        if self.wraps() { self.sub_u } else { self.sub_u + 1 }
+    }
+
+    // the rows `first_ring` to `last_ring` are rings, the other rows
+    // are collapsed to a pole
+    fn first_ring(&self) -> uint {
+        if self.top_pole() { 1 } else { 0 }
+    }
+
+    fn last_ring(&self) -> uint {
+        if self.bottom_pole() { self.sub_v - 1 } else { self.sub_v }
+    }
+
+    // the shared index of the point `u` on row `v`
+    fn index(&self, u: uint, v: uint) -> uint {
+        if v == 0 && self.top_pole() {
+            0
+        } else if v == self.sub_v && self.bottom_pole() {
+            self.shared_vertex_count() - 1
+        } else {
+            let u = if self.wraps() { u % self.sub_u } else { u };
+            self.first_ring() + (v - self.first_ring()) * self.ring_len() + u
+        }
+    }
+
+    // is the row `v` emitted as a triangle fan around a pole
+    fn fan(&self, v: uint) -> bool {
+        self.poles == PoleStyle::Triangle &&
+        ((v == 0 && self.top_pole()) || (v == self.sub_v - 1 && self.bottom_pole()))
+    }
+
+    // flip a counter clockwise polygon if the sphere is clockwise
+    fn wind<P>(&self, p: Polygon<P>) -> Polygon<P> {
+        match self.winding {
+            Winding::CounterClockwise => p,
+            Winding::Clockwise => p.reverse_winding()
+        }
+    }
+
+    fn face(&self, u: uint, v: uint) -> Polygon<(T, T, T)> {
+        let x = self.vert(u,   v);
+        let y = self.vert(u,   v+1);
+        let z = self.vert(u+1, v+1);
+        let w = self.vert(u+1, v);
+
+        self.wind(if !self.fan(v) {
+            PolyQuad(Quad::new(x, y, z, w))
+        } else if v == 0 {
+            PolyTri(Triangle::new(x, y, z))
+        } else {
+            PolyTri(Triangle::new(z, w, x))
+        })
+    }
+}
+
+/// Shows the size of the sphere, for example
+/// `SphereUV { equator: 32, poles: 16, polygons: 512, vertices: 482 }`
+impl<T: Float + FloatMath> fmt::Show for GenericSphereUV<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SphereUV {{ equator: {}, poles: {}, polygons: {}, vertices: {} }}",
+               self.sub_u,
+               self.sub_v,
+               self.indexed_polygon_count(),
+               self.shared_vertex_count())
+    }
+}
+
+/// The default sphere has 32 segments around the equator and 16 from
+/// pole to pole.
+impl Default for SphereUV {
+    fn default() -> SphereUV {
+        SphereUV::builder().build()
+    }
 }
 
-impl Iterator<Polygon<(f32, f32, f32)>> for SphereUV {
-    fn next(&mut self) -> Option<Polygon<(f32, f32, f32)>> {
+/// Configures a `SphereUV`, see `SphereUV::builder`.
+#[deriving(Copy, Clone)]
+pub struct SphereUVBuilder {
+    sub_u: uint,
+    sub_v: uint,
+    radius: f32,
+    center: (f32, f32, f32),
+    poles: PoleStyle
+}
+
+impl SphereUVBuilder {
+    /// the number of points across the equator of the sphere, this is
+    /// the `u` argument of `SphereUV::new`
+    pub fn equator_segments(self, u: uint) -> SphereUVBuilder {
+        SphereUVBuilder { sub_u: u, ..self }
+    }
+
+    /// the number of points from pole to pole, this is the `v`
+    /// argument of `SphereUV::new`
+    pub fn pole_segments(self, v: uint) -> SphereUVBuilder {
+        SphereUVBuilder { sub_v: v, ..self }
+    }
+
+    /// the radius of the sphere
+    pub fn radius(self, radius: f32) -> SphereUVBuilder {
+        SphereUVBuilder { radius: radius, ..self }
+    }
+
+    /// the center of the sphere
+    pub fn center(self, center: (f32, f32, f32)) -> SphereUVBuilder {
+        SphereUVBuilder { center: center, ..self }
+    }
+
+    /// how the rows around the poles are built
+    pub fn pole_style(self, poles: PoleStyle) -> SphereUVBuilder {
+        SphereUVBuilder { poles: poles, ..self }
+    }
+
+    /// create the sphere, this panics if either segment count is less
+    /// than 3 since the sphere would be degenerate
+    pub fn build(self) -> SphereUV {
+        if self.sub_u < 3 {
+            panic!("a sphere needs at least 3 equator segments, got {}", self.sub_u);
+        }
+        if self.sub_v < 3 {
+            panic!("a sphere needs at least 3 pole segments, got {}", self.sub_v);
+        }
+        SphereUV::at(self.sub_u, self.sub_v, self.center, self.radius)
+            .pole_style(self.poles)
+    }
+}
+
+impl<T: Float + FloatMath> Iterator<Polygon<(T, T, T)>> for GenericSphereUV<T> {
+    fn next(&mut self) -> Option<Polygon<(T, T, T)>> {
+        if self.front() >= self.back {
+            return None;
+        }
+
         if self.u == self.sub_u {
             self.u = 0;
             self.v += 1;
-            if self.v == self.sub_v {
-                return None;
-            }
         }
 
-        let x = self.vert(self.u,   self.v);
-        let y = self.vert(self.u,   self.v+1);
-        let z = self.vert(self.u+1, self.v+1);
-        let w = self.vert(self.u+1, self.v);
-        let v = self.v;
+        let (u, v) = (self.u, self.v);
         self.u += 1;
+        Some(self.face(u, v))
+    }
 
-        if v == 0 {
-            Some(PolyTri(Triangle::new(x, y, z)))
-        } else if v == self.sub_v - 1 {
-            Some(PolyTri(Triangle::new(z, w, x)))
-        } else {
-            Some(PolyQuad(Quad::new(x, y, z, w)))
-        }
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        // each cell produces exactly one polygon
+        let n = self.back - self.front();
+        (n, Some(n))
     }
 }
 
-impl SharedVertex<(f32, f32, f32)> for SphereUV {
-    fn shared_vertex(&self, idx: uint) -> (f32, f32, f32) {
-        if idx == 0 {
-            self.vert(0, 0)
-        } else if idx == self.shared_vertex_count() - 1 {
-            self.vert(0, self.sub_v)
-        } else {
-            // since the bottom verts all map to the same
-            // we jump over them in index space
-            let idx = idx - 1;
-            let u = idx % (self.sub_u);
-            let v = idx / (self.sub_u);
-            self.vert(u, v+1)
+impl<T: Float + FloatMath> DoubleEndedIterator<Polygon<(T, T, T)>> for GenericSphereUV<T> {
+    fn next_back(&mut self) -> Option<Polygon<(T, T, T)>> {
+        if self.front() >= self.back {
+            return None;
         }
+
+        self.back -= 1;
+        let u = self.back % self.sub_u;
+        let v = self.back / self.sub_u;
+        Some(self.face(u, v))
+    }
+}
+
+impl<T: Float + FloatMath> ExactSizeIterator<Polygon<(T, T, T)>> for GenericSphereUV<T> {}
+
+impl<T: Float + FloatMath> SharedVertex<(T, T, T)> for GenericSphereUV<T> {
+    fn shared_vertex(&self, idx: uint) -> (T, T, T) {
+        let (u, v) = self.shared_coords(idx);
+        self.vert(u, v)
     }
 
     fn shared_vertex_count(&self) -> uint {
-        (self.sub_v - 1) * (self.sub_u) + 2
+        let poles = self.first_ring() + (self.sub_v - self.last_ring());
+        (self.last_ring() - self.first_ring() + 1) * self.ring_len() + poles
     }
 }
 
-impl IndexedPolygon<Polygon<uint>> for SphereUV {
+impl<T: Float + FloatMath> IndexedPolygon<Polygon<uint>> for GenericSphereUV<T> {
     fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
         let u = idx % self.sub_u;
         let v = idx / self.sub_u;
 
-        let f = |u: uint, v: uint| {
-            if v == 0 {
-                0
-            } else if self.sub_v == v {
-                (self.sub_v-1) * (self.sub_u) + 1
-            } else {
-                (v-1) * self.sub_u + (u % self.sub_u) + 1
-            }
-        };
+        let f = |u: uint, v: uint| self.index(u, v);
 
-        if v == 0 {
+        self.wind(if !self.fan(v) {
+            PolyQuad(Quad::new(f(u,   v),
+                               f(u,   v+1),
+                               f(u+1, v+1),
+                               f(u+1, v)))
+        } else if v == 0 {
             PolyTri(Triangle::new(f(u,   v),
                                   f(u,   v+1),
                                   f(u+1, v+1)))
-        } else if self.sub_v - 1 == v {
+        } else {
             PolyTri(Triangle::new(f(u+1, v+1),
                                   f(u+1, v),
                                   f(u,   v)))
-        } else {
-            PolyQuad(Quad::new(f(u,   v),
-                               f(u,   v+1),
-                               f(u+1, v+1),
-                               f(u+1, v)))
-        }
+        })
     }
 
     fn indexed_polygon_count(&self) -> uint {
@@ -134,3 +423,186 @@ impl IndexedPolygon<Polygon<uint>> for SphereUV {
     }
 }
 
+/// The rows next to the poles are drawn as strips as well, so the pole
+/// fans are made of pairs of a triangle and a degenerate triangle.
+impl<T: Float + FloatMath> TriangleStrip for GenericSphereUV<T> {
+    fn triangle_strip(&self) -> Vec<uint> {
+        let mut strip = Vec::with_capacity(self.sub_v * (2 * self.sub_u + 4));
+        for v in range(0, self.sub_v) {
+            if v != 0 {
+                strip.push(PRIMITIVE_RESTART);
+            }
+
+            // a clockwise sphere walks each row the other way around
+            let column = |i: uint| match self.winding {
+                Winding::CounterClockwise => i,
+                Winding::Clockwise => self.sub_u - i
+            };
+            strip.push(self.index(column(0), v+1));
+            for i in range(0, self.sub_u + 1) {
+                strip.push(self.index(column(i), v+1));
+                strip.push(self.index(column(i), v));
+            }
+        }
+        strip
+    }
+}
+
+
+/// A `SphereUV` where each vertex is paired with a texture coordinate
+/// `(u, v)`. `u` runs from 0 to 1 around the equator and `v` runs from
+/// 0 at the top pole to 1 at the bottom pole. A partial sphere only
+/// covers the same part of the texture as it does of the sphere, so its
+/// texture coordinates line up with those of the whole sphere.
+///
+/// The texture wraps at the seam, so the vertices along the seam and at
+/// the poles are duplicated with distinct texture coordinates. This means
+/// there are more shared vertices than in the position only `SphereUV`.
+#[deriving(Copy)]
+pub struct SphereUVTexCoords<T> {
+    sphere: GenericSphereUV<T>,
+    idx: uint,
+    centered_poles: bool
+}
+
+impl<T: Float + FloatMath> SphereUVTexCoords<T> {
+    /// Place the apex of every triangle in a pole fan at the middle of
+    /// the `u` span of its base, rather than at the `u` of its left
+    /// edge. This reduces how much the texture is pinched at the poles.
+    ///
+    /// Each pole triangle gets its own apex vertex with its own texture
+    /// coordinate, so the pole is never shared between triangles. This
+    /// only changes spheres that use `PoleStyle::Triangle`.
+    pub fn centered_poles(self) -> SphereUVTexCoords<T> {
+        SphereUVTexCoords {
+            centered_poles: true,
+            ..self
+        }
+    }
+
+    fn index(&self, u: uint, v: uint) -> uint {
+        v * (self.sphere.sub_u + 1) + u
+    }
+
+    // is row `v` the apex of a pole fan
+    fn apex(&self, v: uint) -> bool {
+        (v == 0 && self.sphere.fan(0)) ||
+        (v == self.sphere.sub_v && self.sphere.fan(self.sphere.sub_v - 1))
+    }
+}
+
+impl<T: Float + FloatMath> Iterator<Polygon<((T, T, T), (T, T))>> for SphereUVTexCoords<T> {
+    fn next(&mut self) -> Option<Polygon<((T, T, T), (T, T))>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl<T: Float + FloatMath> SharedVertex<((T, T, T), (T, T))> for SphereUVTexCoords<T> {
+    fn shared_vertex(&self, idx: uint) -> ((T, T, T), (T, T)) {
+        let u = idx % (self.sphere.sub_u + 1);
+        let v = idx / (self.sphere.sub_u + 1);
+
+        // the apex used by the triangle in column `u` is moved half a
+        // column along to sit over the middle of its base
+        let tu = if self.centered_poles && self.apex(v) {
+            float::<T>(2 * u + 1) / float(2 * self.sphere.sub_u)
+        } else {
+            float::<T>(u) / float(self.sphere.sub_u)
+        };
+
+        let tv = float::<T>(v) / float(self.sphere.sub_v);
+
+        let (u0, u1) = self.sphere.u_range;
+        let (v0, v1) = self.sphere.v_range;
+        (self.sphere.vert(u, v),
+         (u0 + (u1 - u0) * tu, v0 + (v1 - v0) * tv))
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        (self.sphere.sub_u + 1) * (self.sphere.sub_v + 1)
+    }
+}
+
+impl<T: Float + FloatMath> IndexedPolygon<Polygon<uint>> for SphereUVTexCoords<T> {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        let u = idx % self.sphere.sub_u;
+        let v = idx / self.sphere.sub_u;
+
+        self.sphere.wind(if !self.sphere.fan(v) {
+            PolyQuad(Quad::new(self.index(u,   v),
+                               self.index(u,   v+1),
+                               self.index(u+1, v+1),
+                               self.index(u+1, v)))
+        } else if v == 0 {
+            PolyTri(Triangle::new(self.index(u,   v),
+                                  self.index(u,   v+1),
+                                  self.index(u+1, v+1)))
+        } else {
+            PolyTri(Triangle::new(self.index(u,   v+1),
+                                  self.index(u+1, v),
+                                  self.index(u,   v)))
+        })
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        self.sphere.sub_v * self.sphere.sub_u
+    }
+}
+
+/// A `SphereUV` where each vertex is paired with its normal. The sphere
+/// has the same shared vertices and polygons as the `SphereUV` it was
+/// made from, so a pole has a single normal pointing along the axis.
+#[deriving(Copy)]
+pub struct SphereUVNormals<T> {
+    sphere: GenericSphereUV<T>,
+    idx: uint
+}
+
+impl<T: Float + FloatMath> Iterator<Polygon<((T, T, T), (T, T, T))>> for SphereUVNormals<T> {
+    fn next(&mut self) -> Option<Polygon<((T, T, T), (T, T, T))>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl<T: Float + FloatMath> SharedVertex<((T, T, T), (T, T, T))> for SphereUVNormals<T> {
+    fn shared_vertex(&self, idx: uint) -> ((T, T, T), (T, T, T)) {
+        let (u, v) = self.sphere.shared_coords(idx);
+        (self.sphere.vert(u, v), self.sphere.unit_vert(u, v))
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        self.sphere.shared_vertex_count()
+    }
+}
+
+impl<T: Float + FloatMath> IndexedPolygon<Polygon<uint>> for SphereUVNormals<T> {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        self.sphere.indexed_polygon(idx)
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        self.sphere.indexed_polygon_count()
+    }
+}
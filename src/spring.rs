@@ -0,0 +1,130 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::f32::consts::PI_2;
+use std::num::FloatMath;
+use super::{Quad, Polygon, MapVertex};
+use super::Polygon::PolyQuad;
+use super::generators::{SharedVertex, IndexedPolygon};
+use math::{Vector3, add, scale, cross, normalize};
+
+/// A tube that winds around the `z` axis in a helix, like a coil spring.
+/// The spring starts on the XY plane at `z = 0` and rises to `height`.
+///
+/// The tube is swept along the helix using the Frenet frame of the helix,
+/// which turns at a constant rate so the tube does not twist. The two ends
+/// of the tube are left open.
+#[deriving(Copy)]
+pub struct Spring {
+    idx: uint,
+    radius: f32,
+    tubular_radius: f32,
+    height: f32,
+    turns: f32,
+    radial_segments: uint,
+    tubular_segments: uint
+}
+
+impl Spring {
+    /// Create a new spring.
+    /// `radius` is the distance from the `z` axis to the center of the tube.
+    /// `tubular_radius` is the radius of the tube.
+    /// `height` is the distance the spring rises along the `z` axis.
+    /// `turns` is the number of times the spring winds around the `z` axis.
+    /// `radial_segments` is the number of segments along the length of the tube.
+    /// `tubular_segments` is the number of segments around the tube.
+    pub fn new(radius: f32,
+               tubular_radius: f32,
+               height: f32,
+               turns: f32,
+               radial_segments: uint,
+               tubular_segments: uint) -> Spring {
+        assert!(radial_segments > 0 && tubular_segments > 2);
+        Spring {
+            idx: 0,
+            radius: radius,
+            tubular_radius: tubular_radius,
+            height: height,
+            turns: turns,
+            radial_segments: radial_segments,
+            tubular_segments: tubular_segments
+        }
+    }
+
+    fn vert(&self, u: uint, v: uint) -> (f32, f32, f32) {
+        let t = u as f32 / self.radial_segments as f32;
+        let a = t * self.turns * PI_2;
+        let v = (v as f32 / self.tubular_segments as f32) * PI_2;
+
+        let center = (self.radius * a.cos(), self.radius * a.sin(), self.height * t);
+        let tangent = normalize((-self.radius * self.turns * PI_2 * a.sin(),
+                                 self.radius * self.turns * PI_2 * a.cos(),
+                                 self.height));
+        // points away from the `z` axis, the binormal is picked so the
+        // tube faces outwards
+        let normal = (a.cos(), a.sin(), 0.);
+        let binormal = cross(normal, tangent);
+
+        add(center, scale(add(scale(normal, v.cos()), scale(binormal, v.sin())),
+                          self.tubular_radius))
+    }
+
+    // only `v` wraps, the tube is open at both ends
+    fn index(&self, u: uint, v: uint) -> uint {
+        u * self.tubular_segments + (v % self.tubular_segments)
+    }
+}
+
+impl Iterator<Polygon<Vector3>> for Spring {
+    fn next(&mut self) -> Option<Polygon<Vector3>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl SharedVertex<Vector3> for Spring {
+    fn shared_vertex(&self, idx: uint) -> Vector3 {
+        self.vert(idx / self.tubular_segments, idx % self.tubular_segments)
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        (self.radial_segments + 1) * self.tubular_segments
+    }
+}
+
+impl IndexedPolygon<Polygon<uint>> for Spring {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        let u = idx / self.tubular_segments;
+        let v = idx % self.tubular_segments;
+
+        PolyQuad(Quad::new(self.index(u,   v),
+                           self.index(u+1, v),
+                           self.index(u+1, v+1),
+                           self.index(u,   v+1)))
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        self.radial_segments * self.tubular_segments
+    }
+}
@@ -0,0 +1,63 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::io::{Writer, IoResult};
+
+use poly::MapVertex;
+use triangulate::EmitTriangles;
+use normal::FaceNormal;
+use math::Vector3;
+use generators::{SharedVertex, IndexedPolygon};
+
+fn write_vector<W: Writer>(w: &mut W, (x, y, z): Vector3) -> IoResult<()> {
+    try!(w.write_le_f32(x));
+    try!(w.write_le_f32(y));
+    w.write_le_f32(z)
+}
+
+/// Write a mesh in the binary STL format. The file starts with an empty
+/// 80 byte header and the number of triangles, followed by each triangle
+/// as its flat normal, its three vertices and an unused attribute count.
+///
+/// STL only stores triangles, so quads are split in two along their
+/// `x`-`z` diagonal before they are written.
+pub fn to_stl_binary<W: Writer,
+                     P: EmitTriangles<uint>,
+                     G: SharedVertex<Vector3> + IndexedPolygon<P>>
+                    (w: &mut W, generator: &G) -> IoResult<()> {
+
+    let mut count = 0u;
+    for p in generator.indexed_polygon_iter() {
+        p.emit_triangles(|_| count += 1);
+    }
+
+    try!(w.write(&[0u8, ..80]));
+    try!(w.write_le_u32(count as u32));
+
+    for p in generator.indexed_polygon_iter() {
+        let mut triangles = Vec::new();
+        p.emit_triangles(|t| triangles.push(t));
+
+        for t in triangles.into_iter() {
+            let t = t.map_vertex(|i| generator.shared_vertex(i));
+            try!(write_vector(w, t.face_normal()));
+            try!(write_vector(w, t.x));
+            try!(write_vector(w, t.y));
+            try!(write_vector(w, t.z));
+            try!(w.write_le_u16(0));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::collections::RingBuf;
+
+use Triangle;
+
+/// Refine a stream of triangles by splitting each triangle into four. A new
+/// vertex is placed at the middle of each edge, the crate can't know how to
+/// average an arbitrary vertex so the `midpoint` function is supplied by the
+/// caller.
+pub trait SubdivideTriangles<SRC, T> {
+    /// split each triangle into three corner triangles and one center triangle
+    fn subdivide_triangles<'a>(self, midpoint: |&T, &T|:'a -> T) -> SubdivideTrianglesIterator<'a, SRC, T>;
+}
+
+impl<T, SRC: Iterator<Triangle<T>>> SubdivideTriangles<SRC, T> for SRC {
+    fn subdivide_triangles<'a>(self, midpoint: |&T, &T|:'a -> T) -> SubdivideTrianglesIterator<'a, SRC, T> {
+        SubdivideTrianglesIterator {
+            source: self,
+            buffer: RingBuf::with_capacity(4),
+            midpoint: midpoint
+        }
+    }
+}
+
+/// an iterator that yields four triangles for each source triangle
+pub struct SubdivideTrianglesIterator<'a, SRC, T> {
+    source: SRC,
+    buffer: RingBuf<Triangle<T>>,
+    midpoint: |&T, &T|:'a -> T
+}
+
+impl<'a, T: Clone, SRC: Iterator<Triangle<T>>> Iterator<Triangle<T>> for SubdivideTrianglesIterator<'a, SRC, T> {
+    fn next(&mut self) -> Option<Triangle<T>> {
+        loop {
+            match self.buffer.pop_front() {
+                Some(t) => return Some(t),
+                None => ()
+            }
+
+            let Triangle{x, y, z} = match self.source.next() {
+                Some(t) => t,
+                None => return None
+            };
+
+            let xy = (self.midpoint)(&x, &y);
+            let yz = (self.midpoint)(&y, &z);
+            let zx = (self.midpoint)(&z, &x);
+
+            self.buffer.push_back(Triangle::new(x, xy.clone(), zx.clone()));
+            self.buffer.push_back(Triangle::new(xy.clone(), y, yz.clone()));
+            self.buffer.push_back(Triangle::new(zx.clone(), yz.clone(), z));
+            self.buffer.push_back(Triangle::new(xy, yz, zx));
+        }
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let (n, _) = self.source.size_hint();
+        (n.saturating_mul(4).saturating_add(self.buffer.len()), None)
+    }
+}
@@ -0,0 +1,85 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::num::Float;
+
+use triangulate::EmitTriangles;
+use generators::{SharedVertex, IndexedPolygon};
+use math::{Vector3, add, sub, scale, dot, cross, normalize};
+
+// any unit vector that is perpendicular to `n`
+fn perpendicular(n: Vector3) -> Vector3 {
+    let (x, y, z) = n;
+    // cross with the axis that is furthest from `n`
+    let axis = if x.abs() <= y.abs() && x.abs() <= z.abs() {
+        (1., 0., 0.)
+    } else if y.abs() <= z.abs() {
+        (0., 1., 0.)
+    } else {
+        (0., 0., 1.)
+    };
+    let t = normalize(cross(n, axis));
+    if t == (0., 0., 0.) { (1., 0., 0.) } else { t }
+}
+
+/// Calculate a tangent for every shared vertex of a mesh of positions
+/// and texture coordinates. The tangent points in the direction that the
+/// `u` texture coordinate increases, which is what a normal map needs.
+/// The result is parallel to the shared vertices.
+///
+/// The tangents of the triangles around a vertex are summed and then made
+/// perpendicular to the normal of the vertex, the normal is calculated
+/// the same way as `generate_normals`. If the texture coordinates of the
+/// triangles give no direction, an arbitrary tangent that is perpendicular
+/// to the normal is used instead.
+pub fn generate_tangents<P: EmitTriangles<uint>,
+                         G: SharedVertex<(Vector3, (f32, f32))> + IndexedPolygon<P>>
+                        (generator: &G) -> Vec<Vector3> {
+    let count = generator.shared_vertex_count();
+    let mut normals = Vec::from_elem(count, (0., 0., 0.));
+    let mut tangents = Vec::from_elem(count, (0., 0., 0.));
+
+    for p in generator.indexed_polygon_iter() {
+        p.emit_triangles(|t| {
+            let (p0, (u0, v0)) = generator.shared_vertex(t.x);
+            let (p1, (u1, v1)) = generator.shared_vertex(t.y);
+            let (p2, (u2, v2)) = generator.shared_vertex(t.z);
+
+            let e1 = sub(p1, p0);
+            let e2 = sub(p2, p0);
+            let (du1, dv1) = (u1 - u0, v1 - v0);
+            let (du2, dv2) = (u2 - u0, v2 - v0);
+
+            // the length is twice the area of the triangle
+            let n = cross(e1, e2);
+            let r = du1 * dv2 - du2 * dv1;
+            let tangent = if r == 0. {
+                (0., 0., 0.)
+            } else {
+                scale(sub(scale(e1, dv2), scale(e2, dv1)), 1. / r)
+            };
+
+            for &i in [t.x, t.y, t.z].iter() {
+                normals[i] = add(normals[i], n);
+                tangents[i] = add(tangents[i], tangent);
+            }
+        });
+    }
+
+    normals.into_iter().zip(tangents.into_iter()).map(|(n, t)| {
+        let n = normalize(n);
+        let t = normalize(sub(t, scale(n, dot(n, t))));
+        if t == (0., 0., 0.) { perpendicular(n) } else { t }
+    }).collect()
+}
@@ -0,0 +1,73 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use core::iter::Range;
+use std::num::Float;
+use super::{MapVertex, Triangle, Polygon};
+use super::Polygon::PolyTri;
+use super::generators::{SharedVertex, IndexedPolygon};
+
+static FACES: [[uint, ..3], ..4] = [[0, 1, 2], [0, 3, 1], [0, 2, 3], [1, 3, 2]];
+
+/// A regular tetrahedron with its 4 vertices on the unit sphere, centered
+/// at (0, 0, 0).
+#[deriving(Copy)]
+pub struct Tetrahedron {
+    range: Range<uint>
+}
+
+impl Tetrahedron {
+    /// create a new tetrahedron generator
+    pub fn new() -> Tetrahedron {
+        Tetrahedron { range: range(0, 4) }
+    }
+
+    fn vert(&self, idx: uint) -> (f32, f32, f32) {
+        let s = 1. / 3f32.sqrt();
+        match idx {
+            0 => ( s,  s,  s),
+            1 => ( s, -s, -s),
+            2 => (-s,  s, -s),
+            3 => (-s, -s,  s),
+            idx => panic!("{} vertex is higher then 4", idx)
+        }
+    }
+}
+
+impl Iterator<Polygon<(f32, f32, f32)>> for Tetrahedron {
+    fn next(&mut self) -> Option<Polygon<(f32, f32, f32)>> {
+        self.range.next().map(|idx| self.indexed_polygon(idx).map_vertex(|i| self.vert(i)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.range.size_hint()
+    }
+}
+
+impl SharedVertex<(f32, f32, f32)> for Tetrahedron {
+    fn shared_vertex(&self, idx: uint) -> (f32, f32, f32) {
+        self.vert(idx)
+    }
+
+    fn shared_vertex_count(&self) -> uint { 4 }
+}
+
+impl IndexedPolygon<Polygon<uint>> for Tetrahedron {
+    fn indexed_polygon(&self, idx: uint) -> Polygon<uint> {
+        let f = FACES[idx];
+        PolyTri(Triangle::new(f[0], f[1], f[2]))
+    }
+
+    fn indexed_polygon_count(&self) -> uint { 4 }
+}
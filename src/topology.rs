@@ -0,0 +1,60 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::collections::HashMap;
+
+use Polygon;
+use neighbors::polygon_edges;
+
+/// The counts that describe the shape of an indexed mesh, see `topology`.
+#[deriving(Copy, Clone, Show, PartialEq, Eq)]
+pub struct Topology {
+    /// the number of shared vertices
+    pub vertices: uint,
+    /// the number of unique undirected edges
+    pub edges: uint,
+    /// the number of polygons
+    pub faces: uint,
+    /// `vertices - edges + faces`, this is 2 for a closed mesh that is
+    /// the shape of a sphere and 1 for a flat sheet with one boundary
+    pub euler_characteristic: int,
+    /// true if every edge is shared by exactly two faces
+    pub closed_manifold: bool
+}
+
+/// Count the vertices, edges and faces of an indexed mesh. Edges that
+/// start and end on the same vertex, such as those of a collapsed pole,
+/// are not counted.
+pub fn topology<T>(vertices: &[T], polygons: &[Polygon<uint>]) -> Topology {
+    let mut edges: HashMap<(uint, uint), uint> = HashMap::new();
+    for p in polygons.iter() {
+        for &(a, b) in polygon_edges(p).iter() {
+            if a == b {
+                continue;
+            }
+            let key = if a < b { (a, b) } else { (b, a) };
+            let count = match edges.get(&key) { Some(&n) => n, None => 0 };
+            edges.insert(key, count + 1);
+        }
+    }
+
+    let closed = !edges.is_empty() && edges.values().all(|&n| n == 2);
+    Topology {
+        vertices: vertices.len(),
+        edges: edges.len(),
+        faces: polygons.len(),
+        euler_characteristic: vertices.len() as int - edges.len() as int + polygons.len() as int,
+        closed_manifold: closed
+    }
+}
@@ -0,0 +1,109 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::f32::consts::PI_2;
+use std::num::FloatMath;
+use super::{Quad, Polygon, MapVertex};
+use super::Polygon::PolyQuad;
+use super::generators::{SharedVertex, IndexedPolygon};
+
+/// A torus centered at (0, 0, 0) lying on the XY plane. The tube of
+/// the torus wraps around the `z` axis.
+#[deriving(Copy)]
+pub struct Torus {
+    idx: uint,
+    radius: f32,
+    tubular_radius: f32,
+    radial_segments: uint,
+    tubular_segments: uint
+}
+
+impl Torus {
+    /// Create a new torus.
+    /// `radius` is the distance from the center of the torus to the center of the tube.
+    /// `tubular_radius` is the radius of the tube.
+    /// `radial_segments` is the number of segments around the center of the torus.
+    /// `tubular_segments` is the number of segments around the tube.
+    pub fn new(radius: f32,
+               tubular_radius: f32,
+               radial_segments: uint,
+               tubular_segments: uint) -> Torus {
+        assert!(radial_segments > 2 && tubular_segments > 2);
+        Torus {
+            idx: 0,
+            radius: radius,
+            tubular_radius: tubular_radius,
+            radial_segments: radial_segments,
+            tubular_segments: tubular_segments
+        }
+    }
+
+    fn vert(&self, u: uint, v: uint) -> (f32, f32, f32) {
+        let u = (u as f32 / self.radial_segments as f32) * PI_2;
+        let v = (v as f32 / self.tubular_segments as f32) * PI_2;
+        let r = self.radius + self.tubular_radius * v.cos();
+
+        (r * u.cos(),
+         r * u.sin(),
+         self.tubular_radius * v.sin())
+    }
+
+    // wraps both `u` and `v` so that the seams reuse the first ring
+    fn index(&self, u: uint, v: uint) -> uint {
+        (u % self.radial_segments) * self.tubular_segments + (v % self.tubular_segments)
+    }
+}
+
+impl Iterator<Polygon<(f32, f32, f32)>> for Torus {
+    fn next(&mut self) -> Option<Polygon<(f32, f32, f32)>> {
+        if self.idx == self.indexed_polygon_count() {
+            return None;
+        }
+
+        let idx = self.idx;
+        self.idx += 1;
+        Some(PolyQuad(self.indexed_polygon(idx).map_vertex(|i| self.shared_vertex(i))))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.indexed_polygon_count() - self.idx;
+        (n, Some(n))
+    }
+}
+
+impl SharedVertex<(f32, f32, f32)> for Torus {
+    fn shared_vertex(&self, idx: uint) -> (f32, f32, f32) {
+        self.vert(idx / self.tubular_segments, idx % self.tubular_segments)
+    }
+
+    fn shared_vertex_count(&self) -> uint {
+        self.radial_segments * self.tubular_segments
+    }
+}
+
+impl IndexedPolygon<Quad<uint>> for Torus {
+    fn indexed_polygon(&self, idx: uint) -> Quad<uint> {
+        let u = idx / self.tubular_segments;
+        let v = idx % self.tubular_segments;
+
+        Quad::new(self.index(u,   v),
+                  self.index(u+1, v),
+                  self.index(u+1, v+1),
+                  self.index(u,   v+1))
+    }
+
+    fn indexed_polygon_count(&self) -> uint {
+        self.radial_segments * self.tubular_segments
+    }
+}
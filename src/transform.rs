@@ -0,0 +1,251 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use poly::MapVertex;
+use std::num::FloatMath;
+
+use math::{Vector3, add, scale, normalize};
+
+/// A 4x4 matrix stored as an array of columns, `m[3]` is the
+/// translation column.
+pub type Matrix4 = [[f32, ..4], ..4];
+
+static IDENTITY: Matrix4 = [[1., 0., 0., 0.],
+                            [0., 1., 0., 0.],
+                            [0., 0., 1., 0.],
+                            [0., 0., 0., 1.]];
+
+// treat `(x, y, z)` as the point `(x, y, z, 1)` and divide the result by `w`
+fn apply(m: &Matrix4, (x, y, z): Vector3) -> Vector3 {
+    let c = |i: uint| m[0][i] * x + m[1][i] * y + m[2][i] * z + m[3][i];
+    let w = c(3);
+    (c(0) / w, c(1) / w, c(2) / w)
+}
+
+/// Move, resize or otherwise transform every position in a stream of
+/// polygons. These are shorthands for the matching `vertex` closure.
+pub trait Transform<SRC> {
+    /// add `offset` to every position
+    fn translate(self, offset: Vector3) -> TransformIterator<SRC>;
+
+    /// multiply every position by `factor`, component by component
+    fn scale(self, factor: Vector3) -> TransformIterator<SRC>;
+
+    /// rotate every position by `angle` radians around `axis`, which
+    /// passes through the origin. The rotation is counter clockwise when
+    /// looking down `axis` towards the origin. `axis` does not need to be
+    /// normalized, a zero length axis leaves the positions unchanged.
+    fn rotate(self, axis: Vector3, angle: f32) -> TransformIterator<SRC>;
+
+    /// multiply every position by the column major matrix `m`, each
+    /// position is treated as a point so the translation is applied.
+    fn transform(self, m: Matrix4) -> TransformIterator<SRC>;
+}
+
+impl<P: MapVertex<Vector3, Vector3, P>, T: Iterator<P>> Transform<T> for T {
+    fn translate(self, (x, y, z): Vector3) -> TransformIterator<T> {
+        let mut m = IDENTITY;
+        m[3] = [x, y, z, 1.];
+        self.transform(m)
+    }
+
+    fn scale(self, (x, y, z): Vector3) -> TransformIterator<T> {
+        let mut m = IDENTITY;
+        m[0][0] = x;
+        m[1][1] = y;
+        m[2][2] = z;
+        self.transform(m)
+    }
+
+    fn rotate(self, axis: Vector3, angle: f32) -> TransformIterator<T> {
+        let (x, y, z) = normalize(axis);
+        let mut m = IDENTITY;
+        if (x, y, z) != (0., 0., 0.) {
+            // the rodrigues rotation formula as a matrix
+            let (s, c) = angle.sin_cos();
+            let t = 1. - c;
+            m[0] = [c + x*x*t,   x*y*t + z*s, x*z*t - y*s, 0.];
+            m[1] = [x*y*t - z*s, c + y*y*t,   y*z*t + x*s, 0.];
+            m[2] = [x*z*t + y*s, y*z*t - x*s, c + z*z*t,   0.];
+        }
+        self.transform(m)
+    }
+
+    fn transform(self, m: Matrix4) -> TransformIterator<T> {
+        TransformIterator {
+            source: self,
+            matrix: m
+        }
+    }
+}
+
+/// an iterator that transforms the position of every vertex
+pub struct TransformIterator<SRC> {
+    source: SRC,
+    matrix: Matrix4
+}
+
+impl<P: MapVertex<Vector3, Vector3, P>, SRC: Iterator<P>> Iterator<P> for TransformIterator<SRC> {
+    fn next(&mut self) -> Option<P> {
+        let m = self.matrix;
+        self.source.next().map(|p| p.map_vertex(|v| apply(&m, v)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.source.size_hint()
+    }
+}
+
+/// Project every position onto a sphere of `radius` centered at the
+/// origin. Feeding a subdivided cube through this produces a sphere with
+/// much more even faces than `SphereUV`. A position at the origin has no
+/// direction and is left where it is.
+pub trait Spherify<SRC> {
+    /// move every position to `radius` away from the origin
+    fn spherify(self, radius: f32) -> SpherifyIterator<SRC>;
+}
+
+impl<P: MapVertex<Vector3, Vector3, P>, T: Iterator<P>> Spherify<T> for T {
+    fn spherify(self, radius: f32) -> SpherifyIterator<T> {
+        SpherifyIterator {
+            source: self,
+            radius: radius
+        }
+    }
+}
+
+/// an iterator that projects every position onto a sphere
+pub struct SpherifyIterator<SRC> {
+    source: SRC,
+    radius: f32
+}
+
+impl<P: MapVertex<Vector3, Vector3, P>, SRC: Iterator<P>> Iterator<P> for SpherifyIterator<SRC> {
+    fn next(&mut self) -> Option<P> {
+        let radius = self.radius;
+        self.source.next().map(|p| p.map_vertex(|v| scale(normalize(v), radius)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.source.size_hint()
+    }
+}
+
+/// Stamp copies of a base mesh at a number of offsets, such as the trees
+/// of a forest. Every polygon of `base` is emitted translated by the
+/// first offset, then every polygon translated by the second and so on.
+/// The copies are made as they are read, so only the base mesh is held.
+pub struct Instances<P, SRC> {
+    base: Vec<P>,
+    offsets: SRC,
+    offset: Option<Vector3>,
+    idx: uint
+}
+
+impl<P: MapVertex<Vector3, Vector3, P> + Clone, SRC: Iterator<Vector3>> Instances<P, SRC> {
+    /// create an iterator over copies of `base` moved to each offset
+    pub fn new(base: Vec<P>, offsets: SRC) -> Instances<P, SRC> {
+        Instances {
+            base: base,
+            offsets: offsets,
+            offset: None,
+            idx: 0
+        }
+    }
+}
+
+impl<P: MapVertex<Vector3, Vector3, P> + Clone, SRC: Iterator<Vector3>> Iterator<P> for Instances<P, SRC> {
+    fn next(&mut self) -> Option<P> {
+        if self.base.is_empty() {
+            return None;
+        }
+
+        if self.offset.is_none() || self.idx == self.base.len() {
+            self.offset = self.offsets.next();
+            self.idx = 0;
+        }
+
+        let offset = match self.offset {
+            Some(offset) => offset,
+            None => return None
+        };
+        let p = self.base[self.idx].clone();
+        self.idx += 1;
+        Some(p.map_vertex(|v| add(v, offset)))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let n = self.base.len();
+        let current = if self.offset.is_some() { n - self.idx } else { 0 };
+        let (lower, upper) = self.offsets.size_hint();
+        (current + n * lower, upper.map(|u| current + n * u))
+    }
+}
+
+/// Flip, scale or move the texture coordinates of a stream of polygons
+/// whose vertices are `(position, (u, v))` pairs, the positions are left
+/// untouched. Useful when the texture is loaded with a different origin
+/// than the one the mesh was built for.
+pub trait TransformUv<SRC> {
+    /// replace `u` with `1 - u`
+    fn flip_u(self) -> TransformUvIterator<SRC>;
+
+    /// replace `v` with `1 - v`
+    fn flip_v(self) -> TransformUvIterator<SRC>;
+
+    /// multiply every texture coordinate by `factor`
+    fn scale_uv(self, factor: (f32, f32)) -> TransformUvIterator<SRC>;
+
+    /// add `offset` to every texture coordinate
+    fn offset_uv(self, offset: (f32, f32)) -> TransformUvIterator<SRC>;
+}
+
+impl<V, P: MapVertex<(V, (f32, f32)), (V, (f32, f32)), P>, T: Iterator<P>> TransformUv<T> for T {
+    fn flip_u(self) -> TransformUvIterator<T> {
+        TransformUvIterator { source: self, scale: (-1., 1.), offset: (1., 0.) }
+    }
+
+    fn flip_v(self) -> TransformUvIterator<T> {
+        TransformUvIterator { source: self, scale: (1., -1.), offset: (0., 1.) }
+    }
+
+    fn scale_uv(self, factor: (f32, f32)) -> TransformUvIterator<T> {
+        TransformUvIterator { source: self, scale: factor, offset: (0., 0.) }
+    }
+
+    fn offset_uv(self, offset: (f32, f32)) -> TransformUvIterator<T> {
+        TransformUvIterator { source: self, scale: (1., 1.), offset: offset }
+    }
+}
+
+/// an iterator that transforms the texture coordinate of every vertex
+pub struct TransformUvIterator<SRC> {
+    source: SRC,
+    scale: (f32, f32),
+    offset: (f32, f32)
+}
+
+impl<V, P: MapVertex<(V, (f32, f32)), (V, (f32, f32)), P>, SRC: Iterator<P>> Iterator<P>
+    for TransformUvIterator<SRC> {
+    fn next(&mut self) -> Option<P> {
+        let ((su, sv), (ou, ov)) = (self.scale, self.offset);
+        self.source.next().map(|p| p.map_vertex(|(pos, (u, v))| {
+            (pos, (u * su + ou, v * sv + ov))
+        }))
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.source.size_hint()
+    }
+}
@@ -19,20 +19,27 @@ use {
     Quad,
     Triangle,
     Polygon,
+    NGon,
 };
 
 /// provides a way to convert a polygon down to triangles
+///
+/// Unlike `EmitVertices` the polygon is borrowed, so the polygons of a
+/// slice can be split without cloning them first. Use `triangulate` to
+/// turn a whole stream of polygons into triangles.
 pub trait EmitTriangles<T> {
     /// convert a polygon to one or more triangles, each triangle
     /// is returned by calling `emit`
     fn emit_triangles(&self, emit: |Triangle<T>|);
 }
 
+/// A `Quad` is split along its `x`-`z` diagonal, producing the triangles
+/// `(x, y, z)` and `(x, z, w)`. Both keep the winding of the source quad.
 impl<T: Clone> EmitTriangles<T> for Quad<T> {
     fn emit_triangles(&self, emit: |Triangle<T>|) {
-        let &Quad{ref x, ref y, ref z, ref w} = self;
-        emit(Triangle::new(x.clone(), y.clone(), z.clone()));
-        emit(Triangle::new(z.clone(), w.clone(), x.clone()));
+        let (a, b) = self.clone().triangulate();
+        emit(a);
+        emit(b);
     }
 }
 
@@ -51,6 +58,22 @@ impl<T: Clone> EmitTriangles<T> for Polygon<T> {
     }
 }
 
+/// An `NGon` is split into a fan of triangles that all share its first
+/// vertex, an `NGon` of `n` vertices produces `n - 2` triangles. An
+/// `NGon` with fewer than 3 vertices produces none.
+impl<T: Clone> EmitTriangles<T> for NGon<T> {
+    fn emit_triangles(&self, emit: |Triangle<T>|) {
+        let v = self.vertices.as_slice();
+        // `vertices` is public so it may have been emptied after `new`
+        if v.len() < 3 {
+            return;
+        }
+        for i in range(1, v.len() - 1) {
+            emit(Triangle::new(v[0].clone(), v[i].clone(), v[i+1].clone()));
+        }
+    }
+}
+
 /// `Triangluate` is a easy to to convert any Polygon stream to
 /// a stream of triangles. This is useful since Quads and other geometry
 /// are not supported by modern graphics pipelines like OpenGL.
@@ -78,6 +101,13 @@ impl<V, U: EmitTriangles<V>, SRC: Iterator<U>> TriangulateIterator<SRC, V> {
             buffer: RingBuf::new()
         }
     }
+
+    /// The `size_hint` of the polygons that have not been split yet.
+    /// Every one of them produces at least one triangle, so this can be
+    /// used to preallocate when the mix of polygons is known.
+    pub fn source_size_hint(&self) -> (uint, Option<uint>) {
+        self.source.size_hint()
+    }
 }
 
 impl<V, U: EmitTriangles<V>, SRC: Iterator<U>> Iterator<Triangle<V>> for TriangulateIterator<SRC, V> {
@@ -94,4 +124,11 @@ impl<V, U: EmitTriangles<V>, SRC: Iterator<U>> Iterator<Triangle<V>> for Triangu
             }
         }
     }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        // a polygon can produce any number of triangles, so only
+        // the lower bound is known
+        let (n, _) = self.source.size_hint();
+        (n.saturating_add(self.buffer.len()), None)
+    }
 }
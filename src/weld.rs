@@ -0,0 +1,89 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::collections::HashMap;
+use std::num::Float;
+
+use poly::MapVertex;
+use math::Vector3;
+
+type Cell = (i64, i64, i64);
+
+fn cell((x, y, z): Vector3, epsilon: f32) -> Cell {
+    ((x / epsilon).floor() as i64,
+     (y / epsilon).floor() as i64,
+     (z / epsilon).floor() as i64)
+}
+
+fn close((ax, ay, az): Vector3, (bx, by, bz): Vector3, epsilon: f32) -> bool {
+    (ax - bx).abs() <= epsilon &&
+    (ay - by).abs() <= epsilon &&
+    (az - bz).abs() <= epsilon
+}
+
+/// Collapse a stream of polygons with duplicated positions into a
+/// list of shared vertices and polygons of indices into that list.
+///
+/// Two positions are welded together if every component is within
+/// `epsilon` of each other. The first position seen is the one kept.
+/// Positions are bucketed into a spatial hash with cells `epsilon`
+/// wide, so each lookup only compares against the neighboring cells.
+pub fn weld<Q, P: MapVertex<Vector3, uint, Q>, SRC: Iterator<P>>
+           (src: SRC, epsilon: f32) -> (Vec<Vector3>, Vec<Q>) {
+    assert!(epsilon > 0.);
+
+    let mut vertices: Vec<Vector3> = Vec::new();
+    let mut cells: HashMap<Cell, Vec<uint>> = HashMap::new();
+    let mut polygons = Vec::new();
+
+    for p in src {
+        polygons.push(p.map_vertex(|v| {
+            let (cx, cy, cz) = cell(v, epsilon);
+
+            let mut found = None;
+            'search: for dx in range(-1i64, 2) {
+                for dy in range(-1i64, 2) {
+                    for dz in range(-1i64, 2) {
+                        let near = match cells.get(&(cx+dx, cy+dy, cz+dz)) {
+                            Some(near) => near,
+                            None => continue
+                        };
+                        for &i in near.iter() {
+                            if close(vertices[i], v, epsilon) {
+                                found = Some(i);
+                                break 'search;
+                            }
+                        }
+                    }
+                }
+            }
+
+            match found {
+                Some(i) => i,
+                None => {
+                    let i = vertices.len();
+                    vertices.push(v);
+                    match cells.get_mut(&(cx, cy, cz)) {
+                        Some(bucket) => { bucket.push(i); return i; }
+                        None => ()
+                    }
+                    cells.insert((cx, cy, cz), vec![i]);
+                    i
+                }
+            }
+        }));
+    }
+
+    (vertices, polygons)
+}
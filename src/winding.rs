@@ -0,0 +1,130 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::collections::HashMap;
+
+use Polygon::{PolyTri, PolyQuad};
+use lines::EmitLines;
+use generators::IndexedPolygon;
+use {
+    Quad,
+    Triangle,
+    Polygon,
+};
+
+/// The order that the vertices of a front facing polygon are in when
+/// seen from the front.
+#[deriving(Copy, Clone, Show, PartialEq, Eq)]
+pub enum Winding {
+    /// counter clockwise, the OpenGL default and what every generator
+    /// produces unless told otherwise
+    CounterClockwise,
+    /// clockwise
+    Clockwise
+}
+
+/// Reverse the order of the vertices in a polygon, flipping its winding
+/// from counter clockwise to clockwise or the other way around.
+pub trait ReverseWinding {
+    /// consume the polygon and produce one with the opposite winding
+    fn reverse_winding(self) -> Self;
+}
+
+impl<T> ReverseWinding for Triangle<T> {
+    fn reverse_winding(self) -> Triangle<T> {
+        let Triangle{x, y, z} = self;
+        Triangle::new(z, y, x)
+    }
+}
+
+impl<T> ReverseWinding for Quad<T> {
+    fn reverse_winding(self) -> Quad<T> {
+        let Quad{x, y, z, w} = self;
+        Quad::new(w, z, y, x)
+    }
+}
+
+impl<T> ReverseWinding for Polygon<T> {
+    fn reverse_winding(self) -> Polygon<T> {
+        match self {
+            PolyTri(p) => PolyTri(p.reverse_winding()),
+            PolyQuad(p) => PolyQuad(p.reverse_winding())
+        }
+    }
+}
+
+/// Reverse the winding of every polygon in a stream. This is useful for
+/// geometry that is imported with the wrong handedness.
+pub trait Reverse<SRC> {
+    /// convert a stream of polygons into a stream with the opposite winding
+    fn reverse(self) -> ReverseIterator<SRC>;
+}
+
+impl<P: ReverseWinding, T: Iterator<P>> Reverse<T> for T {
+    fn reverse(self) -> ReverseIterator<T> {
+        ReverseIterator {
+            source: self
+        }
+    }
+}
+
+/// an iterator that reverses the winding of each polygon
+pub struct ReverseIterator<SRC> {
+    source: SRC
+}
+
+impl<P: ReverseWinding, SRC: Iterator<P>> Iterator<P> for ReverseIterator<SRC> {
+    fn next(&mut self) -> Option<P> {
+        self.source.next().map(|p| p.reverse_winding())
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.source.size_hint()
+    }
+}
+
+/// Check that the polygons of an indexed mesh have a consistent winding.
+/// Two neighboring polygons agree if they walk their shared edge in
+/// opposite directions. The index of every polygon that walks an edge in
+/// the same direction as a neighbor is returned in ascending order, a
+/// mesh with a consistent winding returns an empty list.
+///
+/// Edges on the boundary of an open mesh only belong to one polygon and
+/// are always fine, as are edges that start and end on the same index.
+pub fn check_winding<P: EmitLines<uint>, G: IndexedPolygon<P>>(generator: &G) -> Vec<uint> {
+    let mut edges: HashMap<(uint, uint), Vec<uint>> = HashMap::new();
+
+    for (i, p) in generator.indexed_polygon_iter().enumerate() {
+        p.emit_lines(|l| {
+            if l.a == l.b {
+                return;
+            }
+            match edges.get_mut(&(l.a, l.b)) {
+                Some(faces) => { faces.push(i); return; }
+                None => ()
+            }
+            edges.insert((l.a, l.b), vec![i]);
+        });
+    }
+
+    let mut offending = Vec::new();
+    for faces in edges.values() {
+        if faces.len() > 1 {
+            offending.push_all(faces.as_slice());
+        }
+    }
+    offending.sort();
+    offending.dedup();
+    offending
+}
@@ -0,0 +1,103 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+use std::cmp;
+
+use Polygon::{PolyTri, PolyQuad};
+use {
+    Quad,
+    Triangle,
+    Polygon,
+};
+
+/// Pair up the vertices of two polygons of the same kind.
+pub trait ZipPolygon<B, P> {
+    /// combine each vertex with the matching vertex of `other`
+    fn zip_polygon(self, other: B) -> P;
+}
+
+impl<A, B> ZipPolygon<Triangle<B>, Triangle<(A, B)>> for Triangle<A> {
+    fn zip_polygon(self, other: Triangle<B>) -> Triangle<(A, B)> {
+        let Triangle{x: ax, y: ay, z: az} = self;
+        let Triangle{x: bx, y: by, z: bz} = other;
+        Triangle::new((ax, bx), (ay, by), (az, bz))
+    }
+}
+
+impl<A, B> ZipPolygon<Quad<B>, Quad<(A, B)>> for Quad<A> {
+    fn zip_polygon(self, other: Quad<B>) -> Quad<(A, B)> {
+        let Quad{x: ax, y: ay, z: az, w: aw} = self;
+        let Quad{x: bx, y: by, z: bz, w: bw} = other;
+        Quad::new((ax, bx), (ay, by), (az, bz), (aw, bw))
+    }
+}
+
+impl<A, B> ZipPolygon<Polygon<B>, Polygon<(A, B)>> for Polygon<A> {
+    /// panics if one polygon is a triangle and the other is a quad
+    fn zip_polygon(self, other: Polygon<B>) -> Polygon<(A, B)> {
+        match (self, other) {
+            (PolyTri(a), PolyTri(b)) => PolyTri(a.zip_polygon(b)),
+            (PolyQuad(a), PolyQuad(b)) => PolyQuad(a.zip_polygon(b)),
+            _ => panic!("can not zip a triangle with a quad")
+        }
+    }
+}
+
+/// Zip two streams of polygons together vertex by vertex. This is the
+/// polygon aware version of `zip`, it is useful for merging attributes
+/// that were generated in separate passes over the same mesh.
+pub trait ZipVertex<SRC, OTHER> {
+    /// combine every polygon with the polygon at the same position in
+    /// `other`. The stream ends when either stream ends.
+    fn zip_vertex(self, other: OTHER) -> ZipVertexIterator<SRC, OTHER>;
+}
+
+impl<P, T: Iterator<P>, OTHER> ZipVertex<T, OTHER> for T {
+    fn zip_vertex(self, other: OTHER) -> ZipVertexIterator<T, OTHER> {
+        ZipVertexIterator {
+            a: self,
+            b: other
+        }
+    }
+}
+
+/// an iterator that pairs the vertices of two polygon streams
+pub struct ZipVertexIterator<SRC, OTHER> {
+    a: SRC,
+    b: OTHER
+}
+
+impl<A: ZipPolygon<B, P>, B, P, SRC: Iterator<A>, OTHER: Iterator<B>>
+    Iterator<P> for ZipVertexIterator<SRC, OTHER> {
+    fn next(&mut self) -> Option<P> {
+        match (self.a.next(), self.b.next()) {
+            (Some(a), Some(b)) => Some(a.zip_polygon(b)),
+            _ => None
+        }
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let (a_lower, a_upper) = self.a.size_hint();
+        let (b_lower, b_upper) = self.b.size_hint();
+
+        let upper = match (a_upper, b_upper) {
+            (Some(a), Some(b)) => Some(cmp::min(a, b)),
+            (Some(a), None) => Some(a),
+            (None, Some(b)) => Some(b),
+            (None, None) => None
+        };
+
+        (cmp::min(a_lower, b_lower), upper)
+    }
+}
@@ -0,0 +1,38 @@
+//   Copyright Colin Sherratt 2014
+//
+//   Licensed under the Apache License, Version 2.0 (the "License");
+//   you may not use this file except in compliance with the License.
+//   You may obtain a copy of the License at
+//
+//       http://www.apache.org/licenses/LICENSE-2.0
+//
+//   Unless required by applicable law or agreed to in writing, software
+//   distributed under the License is distributed on an "AS IS" BASIS,
+//   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//   See the License for the specific language governing permissions and
+//   limitations under the License.
+
+// only uses the parts of the crate that build without the `std` feature,
+// run with `cargo test --no-default-features --test core`
+
+extern crate genmesh;
+
+use genmesh::{Triangle, Quad, Polygon, Vertices, MapToVertices};
+use genmesh::Polygon::{PolyTri, PolyQuad};
+
+#[test]
+fn test_triangle_vertices() {
+    let vertices: Vec<uint> = vec![Triangle::new(1u, 2, 3)].into_iter()
+        .vertex(|v| v * 2)
+        .vertices()
+        .collect();
+    assert_eq!(vertices, vec![2, 4, 6]);
+}
+
+#[test]
+fn test_polygon_vertices() {
+    let polygons: Vec<Polygon<uint>> = vec![PolyTri(Triangle::new(0u, 1, 2)),
+                                            PolyQuad(Quad::new(3u, 4, 5, 6))];
+    let vertices: Vec<uint> = polygons.into_iter().vertices().collect();
+    assert_eq!(vertices, vec![0, 1, 2, 3, 4, 5, 6]);
+}
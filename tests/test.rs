@@ -12,20 +12,102 @@
 //   See the License for the specific language governing permissions and
 //   limitations under the License.
 
+#![feature(unboxed_closures)]
+
 extern crate genmesh;
+#[cfg(feature = "serialize")]
+extern crate serialize;
+
+use std::num::{Float, FloatMath};
+use std::f32::consts::PI_2;
+use std::iter::ExactSizeIterator;
+use std::io::{MemWriter, MemReader};
+use std::collections::{HashSet, RingBuf};
+use std::default::Default;
+use std::mem;
 
 use genmesh::{
     Quad,
+    Polygon,
+    NGon,
+    Tagged,
     EmitTriangles,
     Triangle,
     MapToVertices,
+    MapVertex,
     LruIndexer,
+    HashIndexer,
     Indexer,
     Vertices,
-    Triangulate
+    TriangleVertices,
+    EnumerateVertices,
+    Triangulate,
+    FaceNormal,
+    ColorByNormal,
+    Neighbors,
+    HalfEdgeMesh,
+    topology,
+    Reverse,
+    Centroid,
+    Area,
+    MeasurePolygons,
+    BoundingSphere,
+    SubdivideTriangles,
+    IntoPolygon,
+    IntoVertices,
+    EmitVertices,
+    EmitVerticesRef,
+    MapVertexRef,
+    MapToVerticesRef,
+    ZipVertex,
+    Line,
+    Lines,
+    UniqueLines,
+    unique_edges,
+    Transform,
+    Spherify,
+    Instances,
+    TransformUv,
+    ReverseWinding,
+    Winding,
+    check_winding,
+    FlatMapPolygons,
+    FaceIndex,
+    WithAttribute,
+    InspectPolygons,
+    MapToPolygons,
+    Chunks,
+    FoldPolygons,
+    PartitionKinds,
+    GroupByMaterial,
+    SplitNonPlanar,
+    Barycentric,
+    stream_polygons,
+    dual,
+    catmull_clark,
+    simplify,
+    merge_coplanar,
+    curvature,
+    remap_indices,
+    compact,
+    build_buffers,
+    build_buffers_with,
+    optimize_vertex_cache,
+    weld,
+    bounding_box,
+    triangle_quality,
+    generate_normals,
+    generate_crease_normals,
+    generate_tangents
 };
 
-use genmesh::generators::{Cube, Plane};
+use genmesh::Polygon::{PolyTri, PolyQuad};
+use genmesh::Primitive::{PrimPolygon, PrimLine};
+use genmesh::export::{to_obj, to_stl_binary, to_gltf};
+use genmesh::import::from_obj;
+use genmesh::generators::{Capsule, Circle, Cube, Plane, Cylinder, Torus, SphereUV, GenericSphereUV, IcoSphere, PoleStyle};
+use genmesh::generators::{Axes, Axis, HeightGrid, Octahedron, Tetrahedron, Join, Mobius, Orientation, ParametricSurface, RoundedBox, Spring, SharedVertex, IndexedPolygon};
+use genmesh::generators::{TriangleStrip, PRIMITIVE_RESTART};
 
 #[test]
 fn test_quad_vertex() {
@@ -132,7 +214,7 @@ fn test_to_triangles() {
     q.emit_triangles(|v| result.push(v));
 
     assert_eq!(result, vec![Triangle::new(0u, 1, 2),
-                            Triangle::new(2u, 3, 0)]);
+                            Triangle::new(0u, 2, 3)]);
 
     let t = Triangle::new(0u, 1, 2);
     let mut result = Vec::new();
@@ -141,6 +223,32 @@ fn test_to_triangles() {
     assert_eq!(result, vec![Triangle::new(0u, 1, 2)]);
 }
 
+#[test]
+fn test_triangulate_mixed() {
+    let input = vec![PolyTri(Triangle::new(0u, 1, 2)),
+                     PolyQuad(Quad::new(3u, 4, 5, 6)),
+                     PolyQuad(Quad::new(7u, 8, 9, 10)),
+                     PolyTri(Triangle::new(11u, 12, 13))];
+
+    let output: Vec<Triangle<uint>> = input.into_iter().triangulate().collect();
+
+    assert_eq!(output.len(), 6);
+    assert_eq!(output, vec![Triangle::new(0u, 1, 2),
+                            Triangle::new(3u, 4, 5),
+                            Triangle::new(3u, 5, 6),
+                            Triangle::new(7u, 8, 9),
+                            Triangle::new(7u, 9, 10),
+                            Triangle::new(11u, 12, 13)]);
+}
+
+// the generators yield quads wrapped in a `Polygon`
+fn quad<T>(p: Polygon<T>) -> Quad<T> {
+    match p {
+        PolyQuad(q) => q,
+        PolyTri(_) => panic!("expected a quad")
+    }
+}
+
 #[test]
 fn test_plane() {
     let mut plane = Plane::new();
@@ -148,16 +256,17 @@ fn test_plane() {
     let a = plane.next().unwrap();
 
     let Quad {
-        x: (ax, ay),
-        y: (bx, by),
-        z: (cx, cy),
-        w: (dx, dy)
-    } = a;
+        x: (ax, ay, az),
+        y: (bx, by, bz),
+        z: (cx, cy, cz),
+        w: (dx, dy, dz)
+    } = quad(a);
 
-    assert_eq!(ax, -1.); assert_eq!(ay, -1.);
-    assert_eq!(bx,  1.); assert_eq!(by, -1.);
-    assert_eq!(cx,  1.); assert_eq!(cy,  1.);
-    assert_eq!(dx, -1.); assert_eq!(dy,  1.);
+    assert_eq!(ax, -1.); assert_eq!(ay, -1.); assert_eq!(az, 0.);
+    assert_eq!(bx,  1.); assert_eq!(by, -1.); assert_eq!(bz, 0.);
+    assert_eq!(cx,  1.); assert_eq!(cy,  1.); assert_eq!(cz, 0.);
+    assert_eq!(dx, -1.); assert_eq!(dy,  1.); assert_eq!(dz, 0.);
+    assert!(plane.next().is_none());
 }
 
 #[test]
@@ -186,4 +295,2493 @@ fn test_lru_indexer() {
 
     assert_eq!(20, vectices.len());
     assert_eq!(3*6*2, indexes.len());
-}
\ No newline at end of file
+}
+
+fn sub((ax, ay, az): (f32, f32, f32), (bx, by, bz): (f32, f32, f32)) -> (f32, f32, f32) {
+    (ax - bx, ay - by, az - bz)
+}
+
+fn dot((ax, ay, az): (f32, f32, f32), (bx, by, bz): (f32, f32, f32)) -> f32 {
+    ax * bx + ay * by + az * bz
+}
+
+fn cross((ax, ay, az): (f32, f32, f32), (bx, by, bz): (f32, f32, f32)) -> (f32, f32, f32) {
+    (ay * bz - az * by,
+     az * bx - ax * bz,
+     ax * by - ay * bx)
+}
+
+#[test]
+fn test_cube_faces() {
+    let faces: Vec<Polygon<(f32, f32, f32)>> = Cube::new().collect();
+    assert_eq!(faces.len(), 6);
+
+    for &face in faces.iter() {
+        let Quad{x, y, z, w} = quad(face);
+        let n = cross(sub(y, x), sub(z, x));
+
+        // the fourth point must lie on the plane of the first three
+        assert_eq!(dot(n, sub(w, x)), 0.);
+
+        // the center of each face is the direction it should face
+        let (cx, cy, cz) = (x.0 + y.0 + z.0 + w.0,
+                            x.1 + y.1 + z.1 + w.1,
+                            x.2 + y.2 + z.2 + w.2);
+        assert!(dot(n, (cx, cy, cz)) > 0.);
+    }
+}
+
+#[test]
+fn test_face_normal() {
+    let t = Triangle::new((0f32, 0f32, 0f32), (1., 0., 0.), (0., 1., 0.));
+    assert_eq!(t.face_normal(), (0., 0., 1.));
+
+    let q = Quad::new((0f32, 0f32, 0f32), (0., 2., 0.), (2., 2., 0.), (2., 0., 0.));
+    assert_eq!(q.face_normal(), (0., 0., -1.));
+
+    let p = PolyQuad(q).with_face_normal();
+    assert_eq!(p, PolyQuad(Quad::new(((0., 0., 0.), (0., 0., -1.)),
+                                     ((0., 2., 0.), (0., 0., -1.)),
+                                     ((2., 2., 0.), (0., 0., -1.)),
+                                     ((2., 0., 0.), (0., 0., -1.)))));
+
+    // a degenerate triangle should not produce a NaN
+    let t = Triangle::new((0f32, 0f32, 0f32), (1., 1., 1.), (2., 2., 2.));
+    assert_eq!(t.face_normal(), (0., 0., 0.));
+}
+
+#[test]
+fn test_lru_indexer_polygons() {
+    let mut vectices = Vec::new();
+    let polygons: Vec<Polygon<uint>> = {
+        let mut indexer = LruIndexer::new(64, |_, v| vectices.push(v));
+
+        Plane::subdivide(4, 4).vertex(|v| indexer.index(v))
+                              .collect()
+    };
+
+    // every shared corner of the grid is only written once
+    assert_eq!(25, vectices.len());
+    assert_eq!(16, polygons.len());
+    for p in polygons.into_iter().vertices() {
+        assert!(p < vectices.len());
+    }
+}
+
+#[test]
+fn test_neighbors() {
+    // two quads sharing the edge 1-4, with a triangle hanging off 4-5
+    let vertices = vec![(0f32, 0f32, 0f32), (1., 0., 0.), (2., 0., 0.),
+                        (0., 1., 0.), (1., 1., 0.), (2., 1., 0.),
+                        (2., 2., 0.)];
+    let polygons = vec![PolyQuad(Quad::new(0u, 1, 4, 3)),
+                        PolyQuad(Quad::new(1u, 2, 5, 4)),
+                        PolyTri(Triangle::new(4u, 5, 6))];
+
+    let n = Neighbors::new(vertices, polygons);
+
+    assert_eq!(n.polygons().len(), 3);
+    assert_eq!(n.neighbors(0), Some(vec![1]));
+    assert_eq!(n.neighbors(1), Some(vec![2, 0]));
+    assert_eq!(n.neighbors(2), Some(vec![1]));
+    assert_eq!(n.neighbors(3), None);
+
+    // edges are undirected
+    assert_eq!(n.edge_polygons(4, 1), n.edge_polygons(1, 4));
+    // a boundary edge only belongs to one polygon
+    assert_eq!(n.edge_polygons(0, 1).unwrap().len(), 1);
+
+    assert_eq!(n.normals_for_vertex(4, |&v| v).len(), 3);
+    assert_eq!(n.normal_for_vertex(4, |&v| v), (0., 0., 1.));
+}
+
+#[test]
+fn test_cylinder() {
+    let cylinder = Cylinder::subdivide(8, 3);
+    assert_eq!(cylinder.shared_vertex_count(), 8 * 4 + 2);
+    assert_eq!(cylinder.indexed_polygon_count(), 8 * 5);
+
+    let last = cylinder.shared_vertex_count() - 1;
+    let (mut caps, mut sides) = (0u, 0u);
+    for p in cylinder.indexed_polygon_iter() {
+        match p {
+            PolyTri(Triangle{x, y: _, z: _}) => {
+                // every cap triangle uses one of the two center vertices
+                assert!(x == 0 || x == last);
+                caps += 1;
+            }
+            PolyQuad(_) => sides += 1
+        }
+    }
+    assert_eq!(caps, 16);
+    assert_eq!(sides, 24);
+
+    for p in Cylinder::new(8).vertices() {
+        let (x, y, z) = p;
+        assert!(z == -1. || z == 1. || z == 0.);
+        assert!((x * x + y * y - 1.).abs() < 0.0001 || (x == 0. && y == 0.));
+    }
+}
+
+#[test]
+fn test_torus() {
+    let torus = Torus::new(2., 0.5, 12, 8);
+    assert_eq!(torus.shared_vertex_count(), 96);
+    assert_eq!(torus.indexed_polygon_count(), 96);
+
+    // the seams wrap back around to the first ring
+    for q in torus.indexed_polygon_iter() {
+        let Quad{x, y, z, w} = q;
+        assert!(x < 96 && y < 96 && z < 96 && w < 96);
+    }
+    assert_eq!(torus.indexed_polygon(95), Quad::new(95, 7, 0, 88));
+
+    for (x, y, z) in Torus::new(2., 0.5, 12, 8).vertices() {
+        let r = (x * x + y * y).sqrt() - 2.;
+        assert!((r * r + z * z - 0.25).abs() < 0.0001);
+    }
+    assert!(Torus::new(2., 0.5, 12, 8).all(|p| match p { PolyQuad(_) => true, PolyTri(_) => false }));
+}
+
+#[test]
+fn test_sphere_tex_coords() {
+    let sphere = SphereUV::new(8, 4).with_tex_coords();
+    assert_eq!(sphere.shared_vertex_count(), 9 * 5);
+    assert_eq!(sphere.indexed_polygon_count(), 8 * 4);
+
+    for (i, poly) in sphere.indexed_polygon_iter().enumerate() {
+        match poly {
+            // the pole is the first vertex of each triangle fan
+            PolyTri(Triangle{x, y: _, z: _}) => {
+                let (_, (_, v)) = sphere.shared_vertex(x);
+                if i < 8 {
+                    assert_eq!(v, 0.);
+                } else {
+                    assert_eq!(v, 1.);
+                }
+            }
+            PolyQuad(_) => assert!(i >= 8 && i < 24)
+        }
+    }
+
+    // both sides of the seam are present
+    let (_, (u, _)) = sphere.shared_vertex(9);
+    assert_eq!(u, 0.);
+    let (_, (u, _)) = sphere.shared_vertex(17);
+    assert_eq!(u, 1.);
+
+    assert_eq!(SphereUV::new(8, 4).with_tex_coords().count(), 32);
+}
+
+#[test]
+fn test_icosphere() {
+    for &(n, faces, vertices) in [(0u, 20u, 12u), (1, 80, 42), (2, 320, 162)].iter() {
+        let sphere = IcoSphere::subdivide(n);
+        assert_eq!(sphere.indexed_polygon_count(), faces);
+        // a midpoint is never duplicated between neighboring triangles
+        assert_eq!(sphere.shared_vertex_count(), vertices);
+    }
+
+    for (x, y, z) in IcoSphere::subdivide(2).vertices() {
+        assert!((x * x + y * y + z * z - 1.).abs() < 0.0001);
+    }
+
+    for p in IcoSphere::subdivide(1) {
+        let t = match p {
+            PolyTri(t) => t,
+            p => panic!("expected a triangle, got {}", p)
+        };
+        let n = t.face_normal();
+        assert!(dot(n, t.x) > 0.);
+    }
+}
+
+#[test]
+fn test_reverse() {
+    let input = vec![PolyTri(Triangle::new((0f32, 0f32, 0f32), (1., 0., 0.), (0., 1., 0.))),
+                     PolyQuad(Quad::new((0f32, 0f32, 0f32), (1., 0., 0.), (1., 1., 0.), (0., 1., 0.)))];
+
+    let reversed: Vec<Polygon<(f32, f32, f32)>> = input.clone().into_iter().reverse().collect();
+    assert_eq!(reversed[0], PolyTri(Triangle::new((0., 1., 0.), (1., 0., 0.), (0., 0., 0.))));
+    assert_eq!(reversed[1], PolyQuad(Quad::new((0., 1., 0.), (1., 1., 0.), (1., 0., 0.), (0., 0., 0.))));
+
+    for (a, b) in input.iter().zip(reversed.iter()) {
+        assert_eq!(a.face_normal(), (0., 0., 1.));
+        assert_eq!(b.face_normal(), (0., 0., -1.));
+    }
+
+    let twice: Vec<Polygon<(f32, f32, f32)>> = reversed.into_iter().reverse().collect();
+    assert_eq!(twice, input);
+}
+
+#[test]
+fn test_sphere_size_hint() {
+    for &(u, v) in [(3u, 2u), (4, 4), (8, 3), (16, 16)].iter() {
+        let mut sphere = SphereUV::new(u, v);
+        assert_eq!(sphere.size_hint(), (u * v, Some(u * v)));
+        assert_eq!(sphere.len(), u * v);
+
+        // take a few polygons off the front, the hint should track them
+        let mut taken = 0u;
+        while taken < u + 1 {
+            sphere.next();
+            taken += 1;
+        }
+
+        let (lower, upper) = sphere.size_hint();
+        assert_eq!(upper, Some(lower));
+        assert_eq!(lower, sphere.count());
+        assert_eq!(lower, u * v - taken);
+    }
+
+    let mut sphere = SphereUV::new(4, 4);
+    while sphere.next().is_some() {}
+    assert!(sphere.next().is_none());
+    assert_eq!(sphere.size_hint(), (0, Some(0)));
+}
+
+#[test]
+fn test_quad_triangles_share_diagonal() {
+    let mut result = Vec::new();
+    Quad::new('x', 'y', 'z', 'w').emit_triangles(|t| result.push(t));
+
+    assert_eq!(result.len(), 2);
+    let (a, b) = (result[0], result[1]);
+    assert_eq!((a.x, a.z), ('x', 'z'));
+    assert_eq!((b.x, b.y), ('x', 'z'));
+    assert_eq!(a.y, 'y');
+    assert_eq!(b.z, 'w');
+}
+
+#[test]
+fn test_to_obj() {
+    let mut w = MemWriter::new();
+    to_obj(&mut w, &Cube::new()).unwrap();
+
+    let s = std::str::from_utf8(w.get_ref()).unwrap().to_string();
+    let lines: Vec<&str> = s.as_slice().lines().collect();
+
+    assert_eq!(lines.iter().filter(|l| l.starts_with("v ")).count(), 8);
+    assert_eq!(lines.iter().filter(|l| l.starts_with("f ")).count(), 6);
+
+    // indices start at 1
+    assert_eq!(lines[8], "f 1 2 4 3");
+    for l in lines.iter().filter(|l| l.starts_with("f ")) {
+        for idx in l.words().skip(1) {
+            let idx: uint = from_str(idx).unwrap();
+            assert!(idx >= 1 && idx <= 8);
+        }
+    }
+}
+
+#[test]
+fn test_sphere_radius_and_center() {
+    let sphere = SphereUV::at(12, 8, (1., 2., 3.), 2.);
+    for (x, y, z) in sphere.vertices().chain(sphere.shared_vertex_iter()) {
+        let d = dot(sub((x, y, z), (1., 2., 3.)), sub((x, y, z), (1., 2., 3.)));
+        assert!((d.sqrt() - 2.).abs() < 0.0001);
+    }
+
+    for (x, y, z) in SphereUV::with_radius(12, 8, 3.).shared_vertex_iter() {
+        assert!(((x * x + y * y + z * z).sqrt() - 3.).abs() < 0.0001);
+    }
+}
+
+#[test]
+fn test_centroid() {
+    let t = Triangle::new((0f32, 0f32, 0f32), (3., 0., 0.), (0., 3., 0.));
+    assert_eq!(t.centroid(), (1., 1., 0.));
+
+    let q = Quad::new((0f32, 0f32, 1f32), (1., 0., 1.), (1., 1., 1.), (0., 1., 1.));
+    assert_eq!(q.centroid(), (0.5, 0.5, 1.));
+
+    assert_eq!(PolyTri(t).centroid(), t.centroid());
+    assert_eq!(PolyQuad(q).centroid(), q.centroid());
+}
+
+#[test]
+fn test_subdivide_triangles() {
+    let input = vec![Triangle::new(0f32, 2., 4.)];
+    let output: Vec<Triangle<f32>> = input.into_iter()
+        .subdivide_triangles(|&a, &b| (a + b) / 2.)
+        .collect();
+
+    assert_eq!(output, vec![Triangle::new(0., 1., 2.),
+                            Triangle::new(1., 2., 3.),
+                            Triangle::new(2., 3., 4.),
+                            Triangle::new(1., 3., 2.)]);
+}
+
+#[test]
+fn test_quad_triangulate() {
+    let q = Quad::new(0u, 1, 2, 3);
+    let (a, b) = q.triangulate();
+    assert_eq!(a, Triangle::new(0u, 1, 2));
+    assert_eq!(b, Triangle::new(0u, 2, 3));
+
+    // the same split is used by the triangulate stage
+    let mut result = Vec::new();
+    q.emit_triangles(|t| result.push(t));
+    assert_eq!(result, vec![a, b]);
+
+    assert_eq!(a.into_polygon(), PolyTri(a));
+    assert_eq!(q.into_polygon(), PolyQuad(q));
+    assert_eq!(PolyQuad(q).into_polygon(), PolyQuad(q));
+}
+
+#[test]
+fn test_sphere_reverse_iteration() {
+    let forward: Vec<Polygon<(f32, f32, f32)>> = SphereUV::new(6, 5).collect();
+    let mut backward: Vec<Polygon<(f32, f32, f32)>> = SphereUV::new(6, 5).rev().collect();
+    backward.reverse();
+    assert_eq!(forward, backward);
+
+    // take from both ends until they meet in the middle
+    let mut sphere = SphereUV::new(6, 5);
+    let mut taken = Vec::new();
+    loop {
+        match (sphere.next(), sphere.next_back()) {
+            (Some(a), Some(b)) => { taken.push(a); taken.push(b); }
+            (Some(a), None) => { taken.push(a); break; }
+            (None, _) => break
+        }
+    }
+    assert_eq!(taken.len(), 30);
+    assert!(sphere.next().is_none());
+    assert!(sphere.next_back().is_none());
+}
+
+#[test]
+fn test_weld() {
+    let polygons = vec![
+        PolyTri(Triangle::new((0f32, 0f32, 0f32), (1., 0., 0.), (1., 1., 0.))),
+        // a tiny error on the shared edge should still be welded
+        PolyTri(Triangle::new((0.00001, 0., 0.), (1., 1., 0.00001), (0., 1., 0.)))
+    ];
+
+    let (vertices, polygons) = weld(polygons.into_iter(), 0.0001);
+    assert_eq!(vertices.len(), 4);
+    assert_eq!(polygons, vec![PolyTri(Triangle::new(0u, 1, 2)),
+                              PolyTri(Triangle::new(0u, 2, 3))]);
+
+    // the 36 corners of the triangulated cube collapse to its 8 corners
+    let (vertices, _) = weld(Cube::new().triangulate(), 0.5);
+    assert_eq!(vertices.len(), 8);
+}
+
+#[test]
+fn test_map_vertex_ref() {
+    let quad = Quad::new(vec![1u, 2], vec![3], vec![], vec![4, 5, 6]);
+    let lengths = quad.map_vertex_ref(|v| v.len());
+    assert_eq!(lengths, Quad::new(2u, 1, 0, 3));
+    // the original polygon was only borrowed
+    assert_eq!(quad.w, vec![4u, 5, 6]);
+
+    let poly = PolyTri(Triangle::new(vec![1u], vec![2, 3], vec![4]));
+    assert_eq!(poly.map_vertex_ref(|v| v[0]), PolyTri(Triangle::new(1u, 2, 4)));
+    assert_eq!(poly, PolyTri(Triangle::new(vec![1u], vec![2, 3], vec![4])));
+
+    let sums: Vec<Triangle<uint>> = vec![Triangle::new(vec![1u, 2], vec![3], vec![4, 5])]
+        .into_iter()
+        .vertex_ref(|v| v.iter().fold(0, |a, &b| a + b))
+        .collect();
+    assert_eq!(sums, vec![Triangle::new(3u, 3, 9)]);
+}
+
+#[test]
+fn test_sphere_pole_style() {
+    let sphere = SphereUV::new(8, 4);
+    let count = sphere.shared_vertex_count();
+    let last = sphere.indexed_polygon_count() - 1;
+
+    // the fan at each pole shares the one apex vertex
+    match sphere.indexed_polygon(0) {
+        PolyTri(t) => assert_eq!(t.x, 0),
+        p => panic!("expected a triangle, got {}", p)
+    }
+    match sphere.indexed_polygon(last) {
+        PolyTri(t) => assert_eq!(t.x, count - 1),
+        p => panic!("expected a triangle, got {}", p)
+    }
+    assert_eq!(sphere.shared_vertex(0), (0., 0., 1.));
+    let (x, y, z) = sphere.shared_vertex(count - 1);
+    assert!(x.abs() < 1e-6 && y.abs() < 1e-6 && z == -1.);
+
+    // the collapsed style emits degenerate quads on the same vertices
+    let collapsed = SphereUV::new(8, 4).pole_style(PoleStyle::Collapsed);
+    assert_eq!(collapsed.shared_vertex_count(), count);
+    assert_eq!(collapsed.indexed_polygon(0), PolyQuad(Quad::new(0u, 1, 2, 0)));
+    assert_eq!(collapsed.indexed_polygon(last),
+               PolyQuad(Quad::new(24u, count - 1, count - 1, 17)));
+    assert!(collapsed.all(|p| match p { PolyQuad(_) => true, _ => false }));
+}
+
+#[test]
+fn test_circle() {
+    let circle = Circle::new(12);
+    assert_eq!(circle.shared_vertex_count(), 13);
+    assert_eq!(circle.indexed_polygon_count(), 12);
+    assert_eq!(circle.shared_vertex(0), (0., 0., 0.));
+
+    for i in range(1, circle.shared_vertex_count()) {
+        let (x, y, z) = circle.shared_vertex(i);
+        assert!(((x * x + y * y).sqrt() - 1.).abs() < 1e-6);
+        assert_eq!(z, 0.);
+    }
+
+    // every rim vertex starts exactly one triangle and ends exactly one
+    let mut starts = Vec::from_elem(13, 0u);
+    let mut ends = Vec::from_elem(13, 0u);
+    for i in range(0, circle.indexed_polygon_count()) {
+        match circle.indexed_polygon(i) {
+            PolyTri(t) => {
+                assert_eq!(t.x, 0);
+                starts[t.y] += 1;
+                ends[t.z] += 1;
+            }
+            p => panic!("expected a triangle, got {}", p)
+        }
+    }
+    assert_eq!(starts[0], 0);
+    assert_eq!(ends[0], 0);
+    for i in range(1, 13) {
+        assert_eq!(starts[i], 1);
+        assert_eq!(ends[i], 1);
+    }
+
+    // the triangles face up and together they span 2 pi around the center
+    let mut angle = 0.;
+    for p in Circle::new(12) {
+        match p {
+            PolyTri(t) => {
+                let (ax, ay, _) = t.y;
+                let (bx, by, _) = t.z;
+                let cross = ax * by - ay * bx;
+                assert!(cross > 0.);
+                angle += cross.atan2(ax * bx + ay * by);
+            }
+            p => panic!("expected a triangle, got {}", p)
+        }
+    }
+    assert!((angle - PI_2).abs() < 1e-5);
+}
+
+#[test]
+fn test_area() {
+    let t = Triangle::new((0f32, 0f32, 0f32), (1., 0., 0.), (0., 1., 0.));
+    assert_eq!(t.area(), 0.5);
+
+    let q = Quad::new((0f32, 0f32, 0f32), (1., 0., 0.), (1., 1., 0.), (0., 1., 0.));
+    assert_eq!(q.area(), 1.);
+    assert_eq!(PolyQuad(q).area(), 1.);
+
+    // lifting a corner out of the plane, the area depends on which
+    // diagonal is used, the quad is split along `x`-`z`
+    let q = Quad::new((0f32, 0f32, 0f32), (1., 0., 0.), (1., 1., 1.), (0., 1., 0.));
+    let (a, b) = q.triangulate();
+    assert_eq!(q.area(), a.area() + b.area());
+    assert!((q.area() - 2f32.sqrt()).abs() < 1e-6);
+    let other = Triangle::new(q.x, q.y, q.w).area() + Triangle::new(q.y, q.z, q.w).area();
+    assert!((q.area() - other).abs() > 0.01);
+
+    // collinear points have no area
+    let t = Triangle::new((0f32, 0f32, 0f32), (1., 1., 1.), (2., 2., 2.));
+    assert_eq!(t.area(), 0.);
+    let q = Quad::new((1f32, 1f32, 1f32), (1., 1., 1.), (1., 1., 1.), (1., 1., 1.));
+    assert_eq!(q.area(), 0.);
+}
+
+#[test]
+fn test_zip_vertex() {
+    let normals = SphereUV::new(8, 4).map(|p| {
+        let n = p.face_normal();
+        p.map_vertex(|_| n)
+    });
+
+    let zipped: Vec<Polygon<((f32, f32, f32), (f32, f32, f32))>> =
+        SphereUV::new(8, 4).zip_vertex(normals).collect();
+    let expected: Vec<Polygon<((f32, f32, f32), (f32, f32, f32))>> =
+        SphereUV::new(8, 4).map(|p| p.with_face_normal()).collect();
+    assert_eq!(zipped, expected);
+
+    assert_eq!(Plane::subdivide(2, 2).zip_vertex(Plane::new()).size_hint(), (1, Some(1)));
+}
+
+#[test]
+#[should_fail]
+fn test_zip_vertex_mismatch() {
+    let tri = vec![PolyTri(Triangle::new(0u, 1, 2))];
+    let quad = vec![PolyQuad(Quad::new(0u, 1, 2, 3))];
+    for _ in tri.into_iter().zip_vertex(quad.into_iter()) {}
+}
+
+#[test]
+fn test_generate_normals() {
+    let cube = Cube::new();
+    let normals = generate_normals(&cube);
+    assert_eq!(normals.len(), cube.shared_vertex_count());
+
+    // every corner is shared by three faces of the same size, so
+    // the normal points diagonally out of the corner
+    let k = 1. / 3f32.sqrt();
+    for (i, &(nx, ny, nz)) in normals.iter().enumerate() {
+        let (x, y, z) = cube.shared_vertex(i);
+        assert!((nx - x * k).abs() < 1e-6);
+        assert!((ny - y * k).abs() < 1e-6);
+        assert!((nz - z * k).abs() < 1e-6);
+    }
+
+    // the caps of a cylinder are triangles and the sides are quads
+    let cylinder = Cylinder::new(8);
+    let normals = generate_normals(&cylinder);
+    let (x, y, z) = normals[0];
+    assert!(x.abs() < 1e-6 && y.abs() < 1e-6 && (z + 1.).abs() < 1e-6);
+    let (x, y, z) = normals[cylinder.shared_vertex_count() - 1];
+    assert!(x.abs() < 1e-6 && y.abs() < 1e-6 && (z - 1.).abs() < 1e-6);
+    for (i, &n) in normals.iter().enumerate() {
+        let (x, y, z) = cylinder.shared_vertex(i);
+        let (nx, ny, nz) = n;
+        // rim normals lean outwards and towards their cap
+        assert!((nx * nx + ny * ny + nz * nz - 1.).abs() < 1e-5);
+        assert!(nx * x + ny * y >= 0.);
+        assert!(nz * z >= 0.);
+    }
+}
+
+#[test]
+fn test_capsule() {
+    let capsule = Capsule::new(0.5, 2., 8, 4);
+    assert_eq!(capsule.shared_vertex_count(), 66);
+    assert_eq!(capsule.indexed_polygon_count(), 72);
+
+    let mut min = 0f32;
+    let mut max = 0f32;
+    for (_, _, z) in capsule.shared_vertex_iter() {
+        min = min.min(z);
+        max = max.max(z);
+    }
+    assert_eq!(max - min, 3.);
+
+    // the last ring of the top hemisphere and the first ring of the
+    // bottom hemisphere are the two ends of the cylinder
+    for ring in range(3u, 5) {
+        for u in range(0u, 8) {
+            let (x, y, z) = capsule.shared_vertex(1 + ring * 8 + u);
+            assert!(((x * x + y * y).sqrt() - 0.5).abs() < 1e-6);
+            assert!((z.abs() - 1.).abs() < 1e-6);
+        }
+    }
+
+    // every position is shared, there are no cracks at the seams
+    let (vertices, _) = weld(Capsule::new(0.5, 2., 8, 4), 1e-4);
+    assert_eq!(vertices.len(), 66);
+
+    let mut tris = 0u;
+    for p in Capsule::new(0.5, 2., 8, 4) {
+        match p {
+            PolyTri(_) => tris += 1,
+            PolyQuad(_) => ()
+        }
+    }
+    assert_eq!(tris, 16);
+}
+
+#[test]
+fn test_into_vertices() {
+    let mut emitted = Vec::new();
+    Triangle::new(1u, 2, 3).emit_vertices(|v| emitted.push(v));
+    let walked: Vec<uint> = Triangle::new(1u, 2, 3).into_vertices().collect();
+    assert_eq!(walked, emitted);
+
+    let mut emitted = Vec::new();
+    Quad::new(1u, 2, 3, 4).emit_vertices(|v| emitted.push(v));
+    let mut walked = Vec::new();
+    for v in Quad::new(1u, 2, 3, 4).into_vertices() {
+        walked.push(v);
+    }
+    assert_eq!(walked, emitted);
+
+    let poly = PolyQuad(Quad::new(5u, 6, 7, 8));
+    let mut emitted = Vec::new();
+    poly.emit_vertices(|v| emitted.push(v));
+    let walked: Vec<uint> = poly.into_vertices().collect();
+    assert_eq!(walked, emitted);
+    assert_eq!(PolyTri(Triangle::new(1u, 2, 3)).into_vertices().size_hint(), (3, Some(3)));
+}
+
+#[test]
+fn test_to_stl_binary() {
+    let mut w = MemWriter::new();
+    to_stl_binary(&mut w, &Cylinder::new(8)).unwrap();
+    let bytes = w.get_ref();
+
+    // 8 triangles on each cap and 8 quads split in two on the side
+    let count = bytes[80] as uint |
+                bytes[81] as uint << 8 |
+                bytes[82] as uint << 16 |
+                bytes[83] as uint << 24;
+    assert_eq!(count, 32);
+    assert_eq!(bytes.len(), 84 + count * 50);
+}
+
+struct EmptyMesh;
+
+impl SharedVertex<(f32, f32, f32)> for EmptyMesh {
+    fn shared_vertex(&self, _: uint) -> (f32, f32, f32) { panic!("the mesh is empty") }
+    fn shared_vertex_count(&self) -> uint { 0 }
+}
+
+impl IndexedPolygon<Triangle<uint>> for EmptyMesh {
+    fn indexed_polygon(&self, _: uint) -> Triangle<uint> { panic!("the mesh is empty") }
+    fn indexed_polygon_count(&self) -> uint { 0 }
+}
+
+#[test]
+fn test_to_gltf() {
+    let mut gltf = MemWriter::new();
+    let mut bin = MemWriter::new();
+    to_gltf(&mut gltf, &mut bin, "cube.bin", &Cube::new()).unwrap();
+
+    // 8 positions followed by 6 quads split into 12 triangles
+    assert_eq!(bin.get_ref().len(), 8 * 12 + 36 * 4);
+
+    let s = std::str::from_utf8(gltf.get_ref()).unwrap().to_string();
+    assert!(s.as_slice().contains("\"uri\":\"cube.bin\",\"byteLength\":240"));
+    assert!(s.as_slice().contains("\"count\":8,\"type\":\"VEC3\",\"min\":[-1,-1,-1],\"max\":[1,1,1]"));
+    assert!(s.as_slice().contains("\"count\":36,\"type\":\"SCALAR\""));
+    assert!(!s.as_slice().contains("NORMAL"));
+    assert!(!s.as_slice().contains("TEXCOORD_0"));
+
+    // the uri is escaped inside the JSON string
+    let mut gltf = MemWriter::new();
+    let mut bin = MemWriter::new();
+    to_gltf(&mut gltf, &mut bin, "a \"b\"\\c.bin", &Cube::new()).unwrap();
+    let s = std::str::from_utf8(gltf.get_ref()).unwrap().to_string();
+    assert!(s.as_slice().contains("\"uri\":\"a \\\"b\\\"\\\\c.bin\""));
+
+    // glTF has no empty buffers, so an empty mesh is refused
+    let mut gltf = MemWriter::new();
+    let mut bin = MemWriter::new();
+    assert!(to_gltf(&mut gltf, &mut bin, "empty.bin", &EmptyMesh).is_err());
+    assert!(gltf.get_ref().is_empty());
+    assert!(bin.get_ref().is_empty());
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn test_to_gltf_json() {
+    use serialize::json;
+
+    let sphere = SphereUV::new(8, 4).with_normals();
+    let mut gltf = MemWriter::new();
+    let mut bin = MemWriter::new();
+    to_gltf(&mut gltf, &mut bin, "sphere.bin", &sphere).unwrap();
+
+    let mut triangles = 0u;
+    for p in sphere.indexed_polygon_iter() {
+        p.emit_triangles(|_| triangles += 1);
+    }
+    let vertices = sphere.shared_vertex_count();
+
+    let s = std::str::from_utf8(gltf.get_ref()).unwrap();
+    let root = json::from_str(s).unwrap();
+    assert_eq!(root.find("asset").unwrap().find("version").unwrap().as_string(), Some("2.0"));
+
+    let buffer = &root.find("buffers").unwrap().as_array().unwrap()[0];
+    assert_eq!(buffer.find("byteLength").unwrap().as_u64(), Some(bin.get_ref().len() as u64));
+
+    let primitive = &root.find("meshes").unwrap().as_array().unwrap()[0]
+        .find("primitives").unwrap().as_array().unwrap()[0];
+    let accessors = root.find("accessors").unwrap().as_array().unwrap();
+    let count = |name: &str| {
+        let i = primitive.find("attributes").unwrap().find(name).unwrap().as_u64().unwrap();
+        accessors[i as uint].find("count").unwrap().as_u64().unwrap() as uint
+    };
+    assert_eq!(count("POSITION"), vertices);
+    assert_eq!(count("NORMAL"), vertices);
+    assert!(primitive.find("attributes").unwrap().find("TEXCOORD_0").is_none());
+
+    let indices = primitive.find("indices").unwrap().as_u64().unwrap();
+    assert_eq!(accessors[indices as uint].find("count").unwrap().as_u64(), Some(triangles as u64 * 3));
+}
+
+fn flat(u: f32, v: f32) -> (f32, f32, f32) {
+    (u, v, 0.)
+}
+
+#[test]
+fn test_parametric_surface() {
+    let surface = ParametricSurface::new(4, 2, flat);
+    assert_eq!(surface.shared_vertex_count(), 15);
+    assert_eq!(surface.indexed_polygon_count(), 8);
+
+    assert_eq!(surface.shared_vertex(0), (0., 0., 0.));
+    assert_eq!(surface.shared_vertex(4), (1., 0., 0.));
+    assert_eq!(surface.shared_vertex(10), (0., 1., 0.));
+    assert_eq!(surface.shared_vertex(14), (1., 1., 0.));
+    assert_eq!(surface.indexed_polygon(0), PolyQuad(Quad::new(0u, 1, 6, 5)));
+
+    // the flat surface faces up, like a plane
+    let quads: Vec<Polygon<(f32, f32, f32)>> = surface.collect();
+    assert_eq!(quads.len(), 8);
+    for q in quads.iter() {
+        assert_eq!(q.face_normal(), (0., 0., 1.));
+    }
+    assert_eq!(quads[7], PolyQuad(Quad::new((0.75, 0.5, 0.), (1., 0.5, 0.),
+                                            (1., 1., 0.), (0.75, 1., 0.))));
+}
+
+#[test]
+fn test_parametric_surface_closure() {
+    // a cylinder whose radius is only known at runtime
+    let radius = 2.5f32;
+    let surface = ParametricSurface::new(8, 2, |&: u: f32, v: f32| {
+        let angle = u * PI_2;
+        (radius * angle.cos(), radius * angle.sin(), v)
+    });
+
+    for (x, y, _) in surface.shared_vertex_iter() {
+        assert!(((x * x + y * y).sqrt() - radius).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_lines() {
+    let lines: Vec<Line<uint>> = vec![Quad::new(0u, 1, 2, 3)].into_iter().lines().collect();
+    assert_eq!(lines, vec![Line::new(0u, 1), Line::new(1, 2),
+                           Line::new(2, 3), Line::new(3, 0)]);
+
+    // the lines form a closed loop
+    for i in range(0, lines.len()) {
+        assert_eq!(lines[i].b, lines[(i + 1) % lines.len()].a);
+    }
+
+    // a 2x2 grid has 16 edges on its quads but only 12 unique ones
+    let plane = Plane::subdivide(2, 2);
+    assert_eq!(plane.indexed_polygon_iter().lines().count(), 16);
+    assert_eq!(plane.indexed_polygon_iter().unique_lines().count(), 12);
+
+    // the cube is closed so each edge is shared by exactly two faces
+    let cube = Cube::new();
+    assert_eq!(cube.indexed_polygon_iter().lines().count(), 24);
+    assert_eq!(cube.indexed_polygon_iter().unique_lines().count(), 12);
+}
+
+#[test]
+fn test_bounding_box() {
+    let empty: Vec<(f32, f32, f32)> = Vec::new();
+    assert_eq!(bounding_box(empty.into_iter()), None);
+
+    let point = vec![(-3f32, -2f32, -1f32)];
+    assert_eq!(bounding_box(point.into_iter()), Some(((-3., -2., -1.), (-3., -2., -1.))));
+
+    // entirely in negative space
+    let moved = SphereUV::at(8, 8, (-5., -5., -5.), 1.);
+    let ((x0, y0, z0), (x1, y1, z1)) = bounding_box(moved.vertices()).unwrap();
+    assert!(x0 < -5. && y0 < -5. && z0 < -5.);
+    assert!(x1 < -3.9 && y1 < -3.9 && z1 < -3.9);
+
+    let ((x0, y0, z0), (x1, y1, z1)) = bounding_box(SphereUV::new(16, 16).vertices()).unwrap();
+    for &v in [x0, y0, z0].iter() {
+        assert!((v + 1.).abs() < 1e-5);
+    }
+    for &v in [x1, y1, z1].iter() {
+        assert!((v - 1.).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_transform() {
+    for (x, y, z) in SphereUV::new(8, 8).scale((3., 3., 3.)).vertices() {
+        assert!(((x * x + y * y + z * z).sqrt() - 3.).abs() < 1e-5);
+    }
+
+    let offset: Vec<Polygon<(f32, f32, f32)>> = Cube::new().translate((1., 2., 3.)).collect();
+    assert_eq!(offset[0].centroid(), (0., 2., 3.));
+
+    // a pure translation matrix moves the centroid of every face
+    let m = [[1., 0., 0., 0.],
+             [0., 1., 0., 0.],
+             [0., 0., 1., 0.],
+             [10., -4., 0.5, 1.]];
+    for (a, b) in Cube::new().zip(Cube::new().transform(m)) {
+        let (ax, ay, az) = a.centroid();
+        let (bx, by, bz) = b.centroid();
+        assert_eq!((bx - ax, by - ay, bz - az), (10., -4., 0.5));
+    }
+}
+
+#[test]
+fn test_ngon() {
+    let pentagon = NGon::new(vec![0u, 1, 2, 3, 4]);
+
+    let mut triangles = Vec::new();
+    pentagon.emit_triangles(|t| triangles.push(t));
+    assert_eq!(triangles, vec![Triangle::new(0u, 1, 2),
+                               Triangle::new(0u, 2, 3),
+                               Triangle::new(0u, 3, 4)]);
+
+    let triangulated: Vec<Triangle<uint>> = vec![NGon::new(vec![0u, 1, 2, 3, 4, 5])]
+        .into_iter().triangulate().collect();
+    assert_eq!(triangulated.len(), 4);
+
+    let mut empty = pentagon.clone();
+    empty.vertices.clear();
+    let mut count = 0u;
+    empty.emit_triangles(|_| count += 1);
+    assert_eq!(count, 0);
+
+    let doubled = pentagon.clone().map_vertex(|v| v * 2);
+    assert_eq!(doubled, NGon::new(vec![0u, 2, 4, 6, 8]));
+
+    let vertices: Vec<uint> = vec![pentagon].into_iter().vertices().collect();
+    assert_eq!(vertices, vec![0u, 1, 2, 3, 4]);
+}
+
+fn sea_level(_: f32, _: f32) -> f32 {
+    0.
+}
+
+fn hills(x: f32, y: f32) -> f32 {
+    (x * 3.).sin() * (y * 2.).cos()
+}
+
+#[test]
+fn test_height_grid() {
+    let grid = HeightGrid::new(4, 3, sea_level);
+    let plane = Plane::subdivide(4, 3);
+    assert_eq!(grid.shared_vertex_count(), plane.shared_vertex_count());
+    assert_eq!(grid.indexed_polygon_count(), plane.indexed_polygon_count());
+    for i in range(0, plane.shared_vertex_count()) {
+        assert_eq!(grid.shared_vertex(i), plane.shared_vertex(i));
+    }
+    for i in range(0, plane.indexed_polygon_count()) {
+        assert_eq!(grid.indexed_polygon(i), PolyQuad(plane.indexed_polygon(i)));
+    }
+
+    // neighboring quads agree exactly on the height of their corners
+    let grid = HeightGrid::new(6, 6, hills);
+    let (vertices, _) = weld(grid, 1e-6);
+    assert_eq!(vertices.len(), grid.shared_vertex_count());
+    for (x, y, z) in grid.shared_vertex_iter() {
+        assert_eq!(z, hills(x, y));
+    }
+}
+
+#[test]
+fn test_height_grid_heightmap() {
+    // a 3x3 heightmap, as if it had been loaded from a file
+    let heights: Vec<f32> = range(0u, 9).map(|i| i as f32 * 0.25).collect();
+    let grid = HeightGrid::new(2, 2, |&: x: f32, y: f32| {
+        let column = (x + 1.).round() as uint;
+        let row = (y + 1.).round() as uint;
+        heights[row * 3 + column]
+    });
+
+    assert_eq!(grid.shared_vertex_count(), 9);
+    for (i, (_, _, z)) in grid.shared_vertex_iter().enumerate() {
+        assert_eq!(z, heights[i]);
+    }
+    let quads: Vec<Polygon<(f32, f32, f32)>> = grid.collect();
+    assert_eq!(quads.len(), 4);
+}
+
+#[test]
+fn test_enumerate_vertices() {
+    let quad = Quad::new('a', 'b', 'c', 'd');
+    let output: Vec<(uint, char)> = vec![quad].into_iter().enumerate_vertices().collect();
+    assert_eq!(output, vec![(0, 'a'), (1, 'b'), (2, 'c'), (3, 'd')]);
+
+    // the index keeps counting across polygons
+    let output: Vec<(uint, (f32, f32, f32))> = Cube::new().enumerate_vertices().collect();
+    assert_eq!(output.len(), 24);
+    for (i, &(idx, _)) in output.iter().enumerate() {
+        assert_eq!(i, idx);
+    }
+}
+
+// a cube with the winding of one of its faces flipped
+struct FlippedCube;
+
+impl IndexedPolygon<Quad<uint>> for FlippedCube {
+    fn indexed_polygon(&self, idx: uint) -> Quad<uint> {
+        let q = Cube::new().indexed_polygon(idx);
+        if idx == 2 { q.reverse_winding() } else { q }
+    }
+
+    fn indexed_polygon_count(&self) -> uint { 6 }
+}
+
+#[test]
+fn test_check_winding() {
+    assert!(check_winding(&Cube::new()).is_empty());
+    assert!(check_winding(&Plane::subdivide(3, 4)).is_empty());
+    assert!(check_winding(&SphereUV::new(12, 8)).is_empty());
+    assert!(check_winding(&SphereUV::new(12, 8).pole_style(PoleStyle::Collapsed)).is_empty());
+    assert!(check_winding(&Cylinder::subdivide(8, 3)).is_empty());
+    assert!(check_winding(&Torus::new(1., 0.25, 12, 8)).is_empty());
+    assert!(check_winding(&IcoSphere::subdivide(1)).is_empty());
+    assert!(check_winding(&Capsule::new(0.5, 1., 8, 3)).is_empty());
+
+    // the flipped face disagrees with all four of its neighbors
+    assert_eq!(check_winding(&FlippedCube), vec![0u, 1, 2, 4, 5]);
+}
+
+#[test]
+fn test_flat_map_polygons() {
+    // split every quad in half across the middle of `x`-`y` and `z`-`w`
+    let halves: Vec<Quad<(f32, f32, f32)>> = Plane::subdivide(2, 2).flat_map_polygons(|p| {
+        let q = quad(p);
+        let xy = mid(q.x, q.y);
+        let zw = mid(q.z, q.w);
+        vec![Quad::new(q.x, xy, zw, q.w), Quad::new(xy, q.y, q.z, zw)]
+    }).collect();
+
+    assert_eq!(halves.len(), 8);
+    let total: f32 = halves.iter().map(|q| q.area()).fold(0., |a, b| a + b);
+    assert_eq!(total, 4.);
+    assert_eq!(halves[0], Quad::new((-1., -1., 0.), (-0.5, -1., 0.),
+                                    (-0.5, 0., 0.), (-1., 0., 0.)));
+
+    // an empty result drops the polygon
+    assert_eq!(Cube::new().flat_map_polygons(|_| Vec::<Triangle<uint>>::new()).count(), 0);
+}
+
+fn mid((ax, ay, az): (f32, f32, f32), (bx, by, bz): (f32, f32, f32)) -> (f32, f32, f32) {
+    ((ax + bx) * 0.5, (ay + by) * 0.5, (az + bz) * 0.5)
+}
+
+#[test]
+fn test_polygon_hash() {
+    let mut set = HashSet::new();
+    set.insert(PolyTri(Triangle::new(0u, 1, 2)));
+    set.insert(PolyQuad(Quad::new(0u, 1, 2, 3)));
+    set.insert(PolyTri(Triangle::new(0u, 1, 2)));
+    set.insert(PolyTri(Triangle::new(2u, 1, 0)));
+    set.insert(PolyQuad(Quad::new(0u, 1, 2, 3)));
+    assert_eq!(set.len(), 3);
+    assert!(set.contains(&PolyTri(Triangle::new(2u, 1, 0))));
+
+    // the cube has six distinct faces
+    let faces: HashSet<Quad<uint>> = Cube::new().indexed_polygon_iter()
+                                                .chain(Cube::new().indexed_polygon_iter())
+                                                .collect();
+    assert_eq!(faces.len(), 6);
+}
+
+#[test]
+fn test_spring() {
+    let spring = Spring::new(2., 0.25, 3., 4., 256, 8);
+    assert_eq!(spring.shared_vertex_count(), 257 * 8);
+    assert_eq!(spring.indexed_polygon_count(), 256 * 8);
+
+    // the center of each ring of the tube, the ring is symmetric about it
+    let center = |u: uint| {
+        let mut c = (0., 0., 0.);
+        for v in range(0, 8) {
+            let (x, y, z) = spring.shared_vertex(u * 8 + v);
+            let (cx, cy, cz) = c;
+            c = (cx + x / 8., cy + y / 8., cz + z / 8.);
+        }
+        c
+    };
+
+    // the centers follow a helix, 4 turns around a circle of radius 2
+    // while rising by 3
+    let mut length = 0.;
+    for u in range(0u, 256) {
+        let (ax, ay, az) = center(u);
+        let (bx, by, bz) = center(u + 1);
+        let d = sub((bx, by, bz), (ax, ay, az));
+        length += dot(d, d).sqrt();
+    }
+    let expected = ((4. * PI_2 * 2f32).powi(2) + 9.).sqrt();
+    assert!((length - expected).abs() / expected < 0.001);
+
+    // the tube keeps its radius and faces away from its center
+    let normals = generate_normals(&spring);
+    for u in range(0u, 257) {
+        let c = center(u);
+        for v in range(0u, 8) {
+            let p = spring.shared_vertex(u * 8 + v);
+            let d = sub(p, c);
+            assert!((dot(d, d).sqrt() - 0.25).abs() < 1e-4);
+            assert!(dot(d, normals[u * 8 + v]) > 0.);
+        }
+    }
+
+    // the tube does not twist between rings
+    for u in range(0u, 256) {
+        let a = spring.shared_vertex(u * 8);
+        let b = spring.shared_vertex((u + 1) * 8);
+        let d = sub(b, a);
+        assert!(dot(d, d).sqrt() < 0.3);
+    }
+}
+
+#[test]
+fn test_face_index() {
+    let input = vec![Quad::new('a', 'b', 'c', 'd'),
+                     Quad::new('e', 'f', 'g', 'h')];
+    let tagged: Vec<Quad<(char, uint)>> = input.into_iter().face_index().collect();
+    assert_eq!(tagged[0], Quad::new(('a', 0), ('b', 0), ('c', 0), ('d', 0)));
+    assert_eq!(tagged[1], Quad::new(('e', 1), ('f', 1), ('g', 1), ('h', 1)));
+
+    let tagged: Vec<Polygon<((f32, f32, f32), uint)>> = SphereUV::new(6, 4).face_index().collect();
+    for (i, p) in tagged.into_iter().enumerate() {
+        p.emit_vertices(|(_, idx)| assert_eq!(idx, i));
+    }
+}
+
+#[test]
+fn test_sphere_builder() {
+    let built: Vec<Polygon<(f32, f32, f32)>> = SphereUV::builder()
+        .equator_segments(12)
+        .pole_segments(7)
+        .build()
+        .collect();
+    let expected: Vec<Polygon<(f32, f32, f32)>> = SphereUV::new(12, 7).collect();
+    assert_eq!(built, expected);
+
+    let built: Vec<Polygon<(f32, f32, f32)>> = SphereUV::builder()
+        .equator_segments(5)
+        .pole_segments(4)
+        .radius(2.)
+        .center((1., 0., 0.))
+        .build()
+        .collect();
+    let expected: Vec<Polygon<(f32, f32, f32)>> = SphereUV::at(5, 4, (1., 0., 0.), 2.).collect();
+    assert_eq!(built, expected);
+
+    let sphere: SphereUV = Default::default();
+    assert_eq!(sphere.indexed_polygon_count(), 32 * 16);
+}
+
+#[test]
+#[should_fail]
+fn test_sphere_builder_degenerate() {
+    SphereUV::builder().equator_segments(2).build();
+}
+
+// a unit square on the XY plane with texture coordinates supplied by `uv`
+struct TexturedQuad {
+    uv: fn((f32, f32, f32)) -> (f32, f32)
+}
+
+impl SharedVertex<((f32, f32, f32), (f32, f32))> for TexturedQuad {
+    fn shared_vertex(&self, idx: uint) -> ((f32, f32, f32), (f32, f32)) {
+        let p = [(0., 0., 0.), (1., 0., 0.), (1., 1., 0.), (0., 1., 0.)][idx];
+        (p, (self.uv)(p))
+    }
+
+    fn shared_vertex_count(&self) -> uint { 4 }
+}
+
+impl IndexedPolygon<Quad<uint>> for TexturedQuad {
+    fn indexed_polygon(&self, _: uint) -> Quad<uint> {
+        Quad::new(0, 1, 2, 3)
+    }
+
+    fn indexed_polygon_count(&self) -> uint { 1 }
+}
+
+fn uv_xy((x, y, _): (f32, f32, f32)) -> (f32, f32) { (x, y) }
+fn uv_yx((x, y, _): (f32, f32, f32)) -> (f32, f32) { (y, x) }
+fn uv_none(_: (f32, f32, f32)) -> (f32, f32) { (0.5, 0.5) }
+
+#[test]
+fn test_generate_tangents() {
+    let tangents = generate_tangents(&TexturedQuad { uv: uv_xy });
+    assert_eq!(tangents, vec![(1., 0., 0.), (1., 0., 0.), (1., 0., 0.), (1., 0., 0.)]);
+
+    let tangents = generate_tangents(&TexturedQuad { uv: uv_yx });
+    assert_eq!(tangents, vec![(0., 1., 0.), (0., 1., 0.), (0., 1., 0.), (0., 1., 0.)]);
+
+    // without a texture direction the tangent still lies in the quad
+    for &(x, y, z) in generate_tangents(&TexturedQuad { uv: uv_none }).iter() {
+        assert_eq!(z, 0.);
+        assert!((x * x + y * y - 1.).abs() < 1e-6);
+    }
+
+    // the poles and the seam of a sphere still get unit tangents
+    let sphere = SphereUV::new(12, 8).with_tex_coords();
+    for &t in generate_tangents(&sphere).iter() {
+        assert!((dot(t, t) - 1.).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_sphere_partial() {
+    let full: Vec<Polygon<(f32, f32, f32)>> = SphereUV::partial(8, 6, (0., 1.), (0., 1.)).collect();
+    let expected: Vec<Polygon<(f32, f32, f32)>> = SphereUV::new(8, 6).collect();
+    assert_eq!(full, expected);
+
+    // the top half keeps its pole but the equator is left open
+    let dome = SphereUV::partial(8, 3, (0., 1.), (0., 0.5));
+    let sphere = SphereUV::new(8, 6);
+    assert_eq!(dome.shared_vertex_count(), 25);
+    assert!(dome.shared_vertex_count() < sphere.shared_vertex_count());
+    assert_eq!(dome.indexed_polygon_count(), 24);
+    for i in range(0u, 8) {
+        let (x, y, z) = dome.shared_vertex(dome.shared_vertex_count() - 1 - i);
+        assert!(((x * x + y * y).sqrt() - 1.).abs() < 1e-6);
+        assert!(z.abs() < 1e-6);
+    }
+    for (_, _, z) in dome.shared_vertex_iter() {
+        assert!(z > -1e-6);
+    }
+    assert!(check_winding(&dome).is_empty());
+
+    // half way around the equator the seam is not shared
+    let wedge = SphereUV::partial(4, 6, (0., 0.5), (0., 1.));
+    assert_eq!(wedge.shared_vertex_count(), 5 * 5 + 2);
+    assert_eq!(wedge.indexed_polygon_count(), 24);
+    for (x, y, z) in wedge.shared_vertex_iter() {
+        assert!(y > -1e-6);
+        assert!(((x * x + y * y + z * z).sqrt() - 1.).abs() < 1e-6);
+    }
+    let (vertices, _) = weld(wedge, 1e-4);
+    assert_eq!(vertices.len(), wedge.shared_vertex_count());
+    assert!(check_winding(&wedge).is_empty());
+}
+
+#[test]
+fn test_sphere_partial_tex_coords() {
+    // the wedge covers columns 2 to 6 and rows 0 to 4 of the whole sphere
+    let wedge = SphereUV::partial(4, 4, (0.25, 0.75), (0., 0.5)).with_tex_coords();
+    let sphere = SphereUV::new(8, 8).with_tex_coords();
+    for v in range(0u, 5) {
+        for u in range(0u, 5) {
+            let (a, (au, av)) = wedge.shared_vertex(v * 5 + u);
+            let (b, (bu, bv)) = sphere.shared_vertex(v * 9 + u + 2);
+            let d = sub(a, b);
+            assert!(dot(d, d) < 1e-10);
+            assert!((au - bu).abs() < 1e-6);
+            assert!((av - bv).abs() < 1e-6);
+        }
+    }
+}
+
+#[test]
+fn test_map_to_polygons() {
+    // drop the triangle fans at the poles
+    let quads: Vec<Quad<(f32, f32, f32)>> = SphereUV::new(8, 6).polygons(|p| {
+        match p {
+            PolyQuad(q) => Some(q),
+            PolyTri(_) => None
+        }
+    }).collect();
+    assert_eq!(quads.len(), 8 * 4);
+
+    // polygons can change kind, each quad becomes its first triangle
+    let tris: Vec<Triangle<uint>> = Plane::subdivide(2, 3).indexed_polygon_iter()
+        .polygons(|q| {
+            let (a, _) = q.triangulate();
+            Some(a)
+        }).collect();
+    assert_eq!(tris.len(), 6);
+}
+
+// f32 is not `Hash` or `Eq`, so positions are keyed by their bits
+fn bits((x, y, z): (f32, f32, f32)) -> (u32, u32, u32) {
+    unsafe {
+        (mem::transmute::<f32, u32>(x),
+         mem::transmute::<f32, u32>(y),
+         mem::transmute::<f32, u32>(z))
+    }
+}
+
+#[test]
+fn test_hash_indexer() {
+    let sphere = IcoSphere::subdivide(2);
+    let polygons: Vec<Polygon<(f32, f32, f32)>> = IcoSphere::subdivide(2).collect();
+
+    // every position of the closed sphere is emitted by more than one
+    // triangle, but is only stored once
+    let mut indexer = HashIndexer::new();
+    let indices: Vec<uint> = polygons.clone()
+                                   .into_iter()
+                                   .vertex(|v| indexer.index(bits(v)))
+                                   .vertices()
+                                   .collect();
+    assert_eq!(indices.len(), sphere.indexed_polygon_count() * 3);
+    assert_eq!(indexer.vertices().len(), sphere.shared_vertex_count());
+    for &i in indices.iter() {
+        assert!(i < sphere.shared_vertex_count());
+    }
+
+    // the indices point back to the positions that were emitted
+    let original: Vec<(f32, f32, f32)> = polygons.clone().into_iter().vertices().collect();
+    let vertices = indexer.into_vertices();
+    for (&i, &o) in indices.iter().zip(original.iter()) {
+        assert_eq!(vertices[i], bits(o));
+    }
+
+    // an lru cache that is too small duplicates vertices
+    let mut emitted = 0u;
+    {
+        let mut lru = LruIndexer::new(4, |_, _| emitted += 1);
+        for v in polygons.into_iter().vertices() {
+            lru.index(bits(v));
+        }
+    }
+    assert!(emitted > sphere.shared_vertex_count());
+}
+
+#[test]
+fn test_cube_tex_coords() {
+    let cube = Cube::new().with_tex_coords();
+    assert_eq!(cube.shared_vertex_count(), 24);
+    assert_eq!(cube.indexed_polygon_count(), 6);
+
+    for p in Cube::new().with_tex_coords() {
+        let q = match p {
+            PolyQuad(q) => q,
+            p => panic!("expected a quad, got {}", p)
+        };
+        let Quad{x: (px, ux), y: (py, uy), z: (pz, uz), w: (pw, uw)} = q;
+        let uv = [ux, uy, uz, uw];
+        let pos = Quad::new(px, py, pz, pw);
+
+        // the four corners cover the unit square
+        for corner in [(0f32, 0f32), (1., 0.), (1., 1.), (0., 1.)].iter() {
+            assert!(uv.iter().any(|c| c == corner));
+        }
+
+        // the texture is upright and not mirrored: moving along `u` is
+        // moving to the right and along `v` is moving up as seen from
+        // outside, which follows the counter clockwise winding
+        let n = pos.face_normal();
+        let right = sub(pos.y, pos.x);
+        let up = sub(pos.w, pos.x);
+        assert_eq!(uv[0], (0., 0.));
+        assert_eq!(uv[1], (1., 0.));
+        assert_eq!(uv[3], (0., 1.));
+        // and the face points out of the cube
+        let c = pos.centroid();
+        assert!(dot(n, c) > 0.);
+        assert!(dot(cross(right, up), c) > 0.);
+        let (_, _, nz) = n;
+        if nz == 0. {
+            assert_eq!(up, (0., 0., 2.));
+        } else {
+            assert_eq!(up, (0., 2., 0.));
+        }
+    }
+}
+
+#[test]
+fn test_chunks() {
+    let chunks: Vec<Vec<Polygon<(f32, f32, f32)>>> = Plane::subdivide(5, 2).chunks(4).collect();
+    let sizes: Vec<uint> = chunks.iter().map(|c| c.len()).collect();
+    assert_eq!(sizes, vec![4, 4, 2]);
+
+    let flat: Vec<Polygon<(f32, f32, f32)>> = Plane::subdivide(5, 2).collect();
+    let mut joined = Vec::new();
+    for c in chunks.iter() {
+        joined.push_all(c.as_slice());
+    }
+    assert_eq!(joined, flat);
+
+    assert_eq!(Plane::subdivide(5, 2).chunks(4).size_hint(), (3, Some(3)));
+    assert_eq!(Plane::subdivide(4, 2).chunks(4).count(), 2);
+}
+
+#[test]
+fn test_sphere_f64() {
+    let sphere = SphereUV::new(16, 9);
+    let precise: GenericSphereUV<f64> = GenericSphereUV::new(16, 9);
+    assert_eq!(precise.shared_vertex_count(), sphere.shared_vertex_count());
+    assert_eq!(precise.indexed_polygon_count(), sphere.indexed_polygon_count());
+    for i in range(0, sphere.indexed_polygon_count()) {
+        assert_eq!(precise.indexed_polygon(i), sphere.indexed_polygon(i));
+    }
+
+    // sin(pi) rounds to a much smaller error in double precision
+    let last = sphere.shared_vertex_count() - 1;
+    let (x, y, z) = sphere.shared_vertex(last);
+    let (px, py, pz) = precise.shared_vertex(last);
+    let error = ((x * x + y * y) as f64).sqrt() + (z as f64 + 1.).abs();
+    let precise_error = (px * px + py * py).sqrt() + (pz + 1.).abs();
+    assert!(precise_error < error);
+    assert!(precise_error < 1e-15);
+
+    let polygons: Vec<Polygon<(f64, f64, f64)>> = precise.collect();
+    assert_eq!(polygons.len(), 16 * 9);
+}
+
+#[test]
+fn test_join() {
+    let cubes = Join::new(Cube::new(), Cube::new());
+    assert_eq!(cubes.shared_vertex_count(), 16);
+    assert_eq!(cubes.indexed_polygon_count(), 12);
+
+    for i in range(0u, 6) {
+        let a: Quad<uint> = cubes.indexed_polygon(i);
+        let b: Quad<uint> = cubes.indexed_polygon(i + 6);
+        assert_eq!(a, Cube::new().indexed_polygon(i));
+        assert_eq!(b, a.map_vertex(|v| v + 8));
+    }
+    for i in range(0u, 8) {
+        assert_eq!(cubes.shared_vertex(i), cubes.shared_vertex(i + 8));
+    }
+
+    // a nested join keeps offsetting by the vertices that come before it
+    let scene = Join::new(Cube::new(), Join::new(Cube::new(), Cube::new()));
+    assert_eq!(scene.shared_vertex_count(), 24);
+    let last: Quad<uint> = scene.indexed_polygon(17);
+    assert_eq!(last, Cube::new().indexed_polygon(5).map_vertex(|v| v + 16));
+}
+
+#[test]
+fn test_sphere_show() {
+    let s = format!("{}", SphereUV::new(32, 16));
+    assert_eq!(s.as_slice(), "SphereUV { equator: 32, poles: 16, polygons: 512, vertices: 482 }");
+
+    let s = format!("{}", SphereUV::partial(8, 3, (0., 1.), (0., 0.5)));
+    assert!(s.as_slice().contains("polygons: 24"));
+}
+
+#[test]
+fn test_dual() {
+    let (vertices, polygons) = dual(&Cube::new());
+    assert_eq!(vertices.len(), 6);
+    assert_eq!(polygons.len(), 8);
+
+    // the dual of a cube is an octahedron, a vertex at the middle of
+    // each face and a triangle at each corner
+    for &(x, y, z) in vertices.iter() {
+        assert_eq!(x.abs() + y.abs() + z.abs(), 1.);
+    }
+    for p in polygons.iter() {
+        assert_eq!(p.vertices.len(), 3);
+        let a = vertices[p.vertices[0]];
+        let b = vertices[p.vertices[1]];
+        let c = vertices[p.vertices[2]];
+        // the triangles face outwards
+        let n = cross(sub(b, a), sub(c, a));
+        assert!(dot(n, a) > 0.);
+    }
+
+    // the dual of an icosahedron is a dodecahedron
+    let (vertices, polygons) = dual(&IcoSphere::new());
+    assert_eq!(vertices.len(), 20);
+    assert_eq!(polygons.len(), 12);
+    for p in polygons.iter() {
+        assert_eq!(p.vertices.len(), 5);
+    }
+}
+
+#[test]
+fn test_emit_vertices_ref() {
+    let tri = Triangle::new(vec![1u], vec![2], vec![3]);
+    let mut lens = Vec::new();
+    tri.emit_vertices_ref(|v| lens.push(v[0]));
+    assert_eq!(lens, vec![1u, 2, 3]);
+    assert_eq!(tri.x, vec![1u]);
+
+    let quad = Quad::new(vec![1u], vec![2, 2], vec![], vec![4]);
+    let mut lens = Vec::new();
+    quad.emit_vertices_ref(|v| lens.push(v.len()));
+    assert_eq!(lens, vec![1u, 2, 0, 1]);
+    assert_eq!(quad.y, vec![2u, 2]);
+
+    let poly = PolyQuad(quad);
+    let mut borrowed = Vec::new();
+    poly.emit_vertices_ref(|v| borrowed.push(v.clone()));
+    let mut emitted = Vec::new();
+    poly.clone().emit_vertices(|v| emitted.push(v));
+    assert_eq!(borrowed, emitted);
+    assert_eq!(poly, PolyQuad(Quad::new(vec![1u], vec![2, 2], vec![], vec![4])));
+}
+
+#[test]
+fn test_remap_indices() {
+    let cylinder = Cylinder::new(6);
+    let count = cylinder.shared_vertex_count();
+    let mut polygons: Vec<Polygon<uint>> = cylinder.indexed_polygon_iter().collect();
+
+    // reverse the vertex buffer, vertex `i` moves to `count - 1 - i`
+    let mut vertices: Vec<(f32, f32, f32)> = cylinder.shared_vertex_iter().collect();
+    vertices.reverse();
+    let map: Vec<uint> = range(0, count).map(|i| count - 1 - i).collect();
+    remap_indices(polygons.as_mut_slice(), map.as_slice());
+
+    let expected: Vec<Polygon<(f32, f32, f32)>> = Cylinder::new(6).collect();
+    let remapped: Vec<Polygon<(f32, f32, f32)>> = polygons.into_iter()
+        .vertex(|i| vertices[i])
+        .collect();
+    assert_eq!(remapped, expected);
+}
+
+// the average number of cache misses per triangle of a fifo cache
+fn cache_miss_ratio(triangles: &[Triangle<uint>], size: uint) -> f32 {
+    let mut cache = RingBuf::new();
+    let mut misses = 0u;
+    for t in triangles.iter() {
+        for &v in [t.x, t.y, t.z].iter() {
+            if !cache.iter().any(|&c| c == v) {
+                misses += 1;
+                cache.push_back(v);
+                if cache.len() > size {
+                    cache.pop_front();
+                }
+            }
+        }
+    }
+    misses as f32 / triangles.len() as f32
+}
+
+#[test]
+fn test_optimize_vertex_cache() {
+    let plane = Plane::subdivide(32, 32);
+    let input: Vec<Triangle<uint>> = plane.indexed_polygon_iter().triangulate().collect();
+    let output = optimize_vertex_cache(input.as_slice());
+
+    // the same triangles come out, only the order changes
+    assert_eq!(output.len(), input.len());
+    let a: HashSet<Triangle<uint>> = input.iter().map(|&t| t).collect();
+    let b: HashSet<Triangle<uint>> = output.iter().map(|&t| t).collect();
+    assert_eq!(a, b);
+
+    // row by row the rows are too long to stay in the cache
+    let before = cache_miss_ratio(input.as_slice(), 16);
+    let after = cache_miss_ratio(output.as_slice(), 16);
+    assert!(before > 1.);
+    assert!(after < 0.8);
+}
+
+#[test]
+fn test_axes() {
+    let axes = Axes::new(8).shaft_radius(0.05).head_radius(0.1).head_length(0.25);
+    assert_eq!(axes.size_hint(), (3 * 4 * 8, Some(3 * 4 * 8)));
+    assert!(check_winding(&axes).is_empty());
+
+    let mut tags = HashSet::new();
+    let mut extent = [0f32, 0., 0.];
+    for p in axes {
+        p.emit_vertices(|((x, y, z), axis)| {
+            tags.insert(axis);
+            let (along, across) = match axis {
+                Axis::X => (x, (y, z)),
+                Axis::Y => (y, (z, x)),
+                Axis::Z => (z, (x, y))
+            };
+            let (a, b) = across;
+            assert!(along >= 0. && along <= 1.);
+            assert!((a*a + b*b).sqrt() <= 0.1 + 1e-6);
+
+            let i = axis as uint;
+            if along > extent[i] {
+                extent[i] = along;
+            }
+        });
+    }
+
+    assert_eq!(tags.len(), 3);
+    assert_eq!(extent, [1., 1., 1.]);
+}
+
+#[test]
+fn test_triangulate_size_hint() {
+    let input = vec![PolyQuad(Quad::new(0u, 1, 2, 3)),
+                     PolyTri(Triangle::new(0u, 1, 2)),
+                     PolyQuad(Quad::new(4u, 5, 6, 7)),
+                     PolyTri(Triangle::new(4u, 5, 6)),
+                     PolyQuad(Quad::new(8u, 9, 10, 11))];
+
+    let mut triangles = input.clone().into_iter().triangulate();
+    assert_eq!(triangles.source_size_hint(), (5, Some(5)));
+    assert_eq!(triangles.size_hint(), (5, None));
+
+    // the second half of the first quad is buffered
+    triangles.next();
+    assert_eq!(triangles.source_size_hint(), (4, Some(4)));
+    assert_eq!(triangles.size_hint(), (5, None));
+
+    assert_eq!(input.into_iter().triangulate().count(), 8);
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn test_polygon_json_round_trip() {
+    use serialize::json;
+
+    let indexed = vec![PolyTri(Triangle::new(0u, 1, 2)),
+                       PolyQuad(Quad::new(3u, 4, 5, 6))];
+    let encoded = json::encode(&indexed);
+    let decoded: Vec<Polygon<uint>> = json::decode(encoded.as_slice()).unwrap();
+    assert_eq!(decoded, indexed);
+
+    // each polygon is tagged with the kind it was
+    assert!(encoded.as_slice().contains("PolyTri"));
+    assert!(encoded.as_slice().contains("PolyQuad"));
+
+    let positions: Vec<Polygon<(f32, f32, f32)>> = Cube::new().collect();
+    let encoded = json::encode(&positions);
+    let decoded: Vec<Polygon<(f32, f32, f32)>> = json::decode(encoded.as_slice()).unwrap();
+    assert_eq!(decoded, positions);
+}
+
+#[test]
+fn test_with_attribute() {
+    // color each face of the cube by the direction it faces
+    let mut calls = 0u;
+    let colored: Vec<Polygon<((f32, f32, f32), (f32, f32, f32))>> = Cube::new()
+        .with_attribute(|q| {
+            calls += 1;
+            let (x, y, z) = q.face_normal();
+            ((x + 1.) / 2., (y + 1.) / 2., (z + 1.) / 2.)
+        })
+        .collect();
+    assert_eq!(calls, 6);
+
+    let mut colors = Vec::new();
+    for q in colored.iter() {
+        let (_, c) = q.into_vertices().next().unwrap();
+        q.emit_vertices_ref(|&(_, v)| assert_eq!(v, c));
+        assert!(!colors.contains(&c));
+        colors.push(c);
+    }
+}
+
+#[test]
+fn test_unique_edges() {
+    // two triangles sharing the edge 0-2
+    let input = [PolyTri(Triangle::new(0u, 1, 2)),
+                 PolyTri(Triangle::new(0u, 2, 3))];
+    assert_eq!(unique_edges(&input),
+               vec![(0, 1), (1, 2), (0, 2), (2, 3), (0, 3)]);
+
+    let cube: Vec<Quad<uint>> = Cube::new().indexed_polygon_iter().collect();
+    assert_eq!(unique_edges(cube.as_slice()).len(), 12);
+}
+
+#[test]
+fn test_half_edge_mesh() {
+    let cube = Cube::new();
+    let vertices: Vec<(f32, f32, f32)> = cube.shared_vertex_iter().collect();
+    let polygons: Vec<Polygon<uint>> = cube.indexed_polygon_iter().map(|q| PolyQuad(q)).collect();
+    let mesh = HalfEdgeMesh::new(vertices, polygons);
+    assert_eq!(mesh.faces().len(), 6);
+    assert_eq!(mesh.vertices().len(), 8);
+    assert_eq!(mesh.edges().len(), 24);
+
+    // the cube is closed, so every half edge has a twin running the other way
+    for e in range(0, mesh.edges().len()) {
+        let twin = mesh.twin(e).unwrap();
+        assert!(mesh.face(twin) != mesh.face(e));
+        assert_eq!(mesh.twin(twin), Some(e));
+        assert_eq!(mesh.vertex(twin), mesh.vertex(mesh.next(e)));
+    }
+
+    // walking `next` goes around the face and back to the start
+    for f in range(0, 6) {
+        let start = mesh.face_edge(f);
+        let mut e = start;
+        for _ in range(0u, 4) {
+            assert_eq!(mesh.face(e), f);
+            e = mesh.next(e);
+        }
+        assert_eq!(e, start);
+    }
+
+    // only the outside of a plane is on the boundary
+    let plane = Plane::subdivide(2, 2);
+    let vertices: Vec<(f32, f32, f32)> = plane.shared_vertex_iter().collect();
+    let polygons: Vec<Polygon<uint>> = plane.indexed_polygon_iter().map(|q| PolyQuad(q)).collect();
+    let mesh = HalfEdgeMesh::new(vertices, polygons);
+    let boundary = range(0, mesh.edges().len()).filter(|&e| mesh.is_boundary(e)).count();
+    assert_eq!(boundary, 8);
+}
+
+#[test]
+fn test_stream_polygons() {
+    // 64 polygons in windows of 10 is six full windows and a remainder of 4
+    let plane = Plane::subdivide(8, 8);
+    let mut sizes = Vec::new();
+    let mut seen = Vec::new();
+    stream_polygons(plane.indexed_polygon_iter(), 10, |window| {
+        sizes.push(window.len());
+        seen.push_all(window);
+    });
+    assert_eq!(sizes, vec![10, 10, 10, 10, 10, 10, 4]);
+
+    let expected: Vec<Quad<uint>> = plane.indexed_polygon_iter().collect();
+    assert_eq!(seen, expected);
+
+    // an exact multiple has no remainder
+    let mut calls = 0u;
+    stream_polygons(plane.indexed_polygon_iter(), 16, |_| calls += 1);
+    assert_eq!(calls, 4);
+}
+
+#[test]
+fn test_platonic_solids() {
+    let tetrahedron = Tetrahedron::new();
+    assert_eq!(tetrahedron.shared_vertex_count(), 4);
+    assert_eq!(tetrahedron.indexed_polygon_count(), 4);
+    assert_eq!(tetrahedron.count(), 4);
+
+    let octahedron = Octahedron::new();
+    assert_eq!(octahedron.shared_vertex_count(), 6);
+    assert_eq!(octahedron.indexed_polygon_count(), 8);
+    assert_eq!(octahedron.count(), 8);
+
+    for (x, y, z) in tetrahedron.shared_vertex_iter().chain(octahedron.shared_vertex_iter()) {
+        assert!(((x*x + y*y + z*z).sqrt() - 1.).abs() < 1e-6);
+    }
+
+    // every face winds counter clockwise when seen from outside
+    assert!(check_winding(&tetrahedron).is_empty());
+    assert!(check_winding(&octahedron).is_empty());
+    for p in tetrahedron.chain(octahedron) {
+        let n = p.face_normal();
+        let c = p.centroid();
+        assert!(dot(n, c) > 0.);
+    }
+}
+
+#[test]
+fn test_spherify() {
+    let projected: Vec<Polygon<(f32, f32, f32)>> = Plane::subdivide(4, 4)
+        .translate((0., 0., 1.))
+        .spherify(1.)
+        .collect();
+    assert_eq!(projected.len(), 16);
+    for q in projected.into_iter() {
+        q.emit_vertices(|(x, y, z)| {
+            assert!(((x*x + y*y + z*z).sqrt() - 1.).abs() < 1e-6);
+        });
+    }
+
+    // the center of the plane is at the origin and is left in place
+    let projected: Vec<Quad<(f32, f32, f32)>> = Plane::subdivide(2, 2).spherify(2.).map(quad).collect();
+    assert_eq!(projected[0].z, (0., 0., 0.));
+    let (x, y, z) = projected[0].x;
+    assert!(((x*x + y*y + z*z).sqrt() - 2.).abs() < 1e-6);
+}
+
+#[test]
+fn test_triangle_quality() {
+    let h = 3f32.sqrt() / 2.;
+    let equilateral = Triangle::new((0., 0., 0.), (1., 0., 0.), (0.5, h, 0.));
+    let quality = triangle_quality(vec![equilateral].into_iter());
+    assert_eq!(quality.triangles, 1);
+    assert_eq!(quality.degenerate, 0);
+    assert!((quality.min_angle - PI_2 / 6.).abs() < 1e-5);
+    assert!((quality.max_aspect_ratio - 1.).abs() < 1e-5);
+
+    // a sliver lowers the minimum angle and a triangle with all three
+    // points on a line is only counted as degenerate
+    let sliver = Triangle::new((0., 0., 0.), (1., 0., 0.), (0.5, 0.01, 0.));
+    let line = Triangle::new((0., 0., 0.), (1., 0., 0.), (2., 0., 0.));
+    let quality = triangle_quality(vec![equilateral, sliver, line].into_iter());
+    assert_eq!(quality.triangles, 3);
+    assert_eq!(quality.degenerate, 1);
+    assert!(quality.min_angle < 0.03);
+    assert!(quality.max_aspect_ratio > 10.);
+
+    // the triangles around the poles of a sphere are the worst
+    let sphere = triangle_quality(SphereUV::new(32, 16).triangulate());
+    assert_eq!(sphere.degenerate, 0);
+    assert!(sphere.min_angle < PI_2 / 12.);
+}
+
+#[test]
+fn test_catmull_clark() {
+    let cube = Cube::new();
+    let (vertices, quads) = catmull_clark(cube.shared_vertex_iter().collect(),
+                                          cube.indexed_polygon_iter().collect());
+    assert_eq!(quads.len(), 24);
+    assert_eq!(vertices.len(), 8 + 6 + 12);
+
+    // the corners are pulled in and the faces pushed out
+    let lengths: Vec<f32> = vertices.iter().map(|&v| dot(v, v).sqrt()).collect();
+    let min = lengths.iter().fold(Float::infinity(), |a, &b| a.min(b));
+    let max = lengths.iter().fold(0f32, |a, &b| a.max(b));
+    assert!(max / min < 1.15);
+
+    // the winding is kept, every quad faces away from the center
+    for &q in quads.iter() {
+        let q = q.map_vertex(|i| vertices[i]);
+        assert!(dot(q.face_normal(), q.centroid()) > 0.);
+    }
+
+    // the corners of a single open quad stay where they are and the
+    // edge points are the middle of each edge
+    let plane = Plane::new();
+    let (vertices, quads) = catmull_clark(plane.shared_vertex_iter().collect(),
+                                          plane.indexed_polygon_iter().collect());
+    assert_eq!(quads.len(), 4);
+    let corners: Vec<(f32, f32, f32)> = plane.shared_vertex_iter().collect();
+    assert_eq!(vertices.slice_to(4), corners.as_slice());
+    assert_eq!(vertices[4], (0., 0., 0.));
+    for &(x, y, _) in vertices.slice_from(5).iter() {
+        assert!((x.abs() == 1. && y == 0.) || (x == 0. && y.abs() == 1.));
+    }
+}
+
+#[test]
+fn test_partition_kinds() {
+    // the two pole rows are triangles and the rows between them quads
+    let sphere = SphereUV::new(8, 6);
+    let total = sphere.len();
+    let (triangles, quads) = sphere.partition_kinds();
+    assert_eq!(triangles.len(), 2 * 8);
+    assert_eq!(quads.len(), 4 * 8);
+    assert_eq!(triangles.len() + quads.len(), total);
+
+    // every triangle touches a pole
+    for t in triangles.iter() {
+        let mut poles = 0u;
+        t.emit_vertices_ref(|&(_, _, z)| if z.abs() == 1. { poles += 1; });
+        assert_eq!(poles, 1);
+    }
+}
+
+#[test]
+fn test_sphere_winding() {
+    let ccw = SphereUV::new(8, 6);
+    let cw = SphereUV::new(8, 6).winding(Winding::Clockwise);
+    let default: Vec<Polygon<(f32, f32, f32)>> = ccw.collect();
+    let explicit: Vec<Polygon<(f32, f32, f32)>> = SphereUV::new(8, 6)
+        .winding(Winding::CounterClockwise)
+        .collect();
+    assert_eq!(default, explicit);
+
+    // every face normal is flipped
+    for (a, b) in ccw.zip(cw) {
+        let (a, b) = (a.face_normal(), b.face_normal());
+        assert!((dot(a, b) + 1.).abs() < 1e-5);
+    }
+
+    // and the indexed polygons and the variants agree with the iterator
+    assert!(check_winding(&cw).is_empty());
+    let indexed = cw.indexed_polygon_iter().vertex(|i| cw.shared_vertex(i));
+    let textured = cw.with_tex_coords().vertex(|(p, _)| p);
+    for ((a, b), c) in cw.zip(indexed).zip(textured) {
+        let a = a.face_normal();
+        assert!(dot(a, b.face_normal()) > 0.99);
+        assert!(dot(a, c.face_normal()) > 0.99);
+    }
+    let precise: GenericSphereUV<f64> = GenericSphereUV::new(8, 6);
+    assert_eq!(precise.winding(Winding::Clockwise).indexed_polygon(3), cw.indexed_polygon(3));
+}
+
+#[test]
+fn test_simplify() {
+    let sphere = SphereUV::new(24, 12);
+    let vertices: Vec<(f32, f32, f32)> = sphere.shared_vertex_iter().collect();
+    let triangles: Vec<Triangle<uint>> = sphere.indexed_polygon_iter().triangulate().collect();
+    let target = triangles.len() / 2;
+
+    let (simple_vertices, simple) = simplify(vertices.as_slice(), triangles.as_slice(), target);
+    assert!(simple.len() <= target);
+    assert!(simple.len() > target - 4);
+    assert!(simple_vertices.len() < vertices.len());
+    for t in simple.iter() {
+        assert!(t.x < simple_vertices.len());
+        assert!(t.y < simple_vertices.len());
+        assert!(t.z < simple_vertices.len());
+    }
+
+    // the simplified sphere still fills about the same box
+    let ((min_x, min_y, min_z), (max_x, max_y, max_z)) =
+        bounding_box(simple_vertices.iter().map(|&v| v)).unwrap();
+    for &v in [min_x, min_y, min_z].iter() {
+        assert!((v + 1.).abs() < 0.05);
+    }
+    for &v in [max_x, max_y, max_z].iter() {
+        assert!((v - 1.).abs() < 0.05);
+    }
+}
+
+#[test]
+fn test_from_obj() {
+    // a cube written by `to_obj` reads back as the same mesh
+    let mut w = MemWriter::new();
+    to_obj(&mut w, &Cube::new()).unwrap();
+    let mut r = MemReader::new(w.unwrap());
+    let (vertices, polygons) = from_obj(&mut r).unwrap();
+
+    let cube = Cube::new();
+    let expected: Vec<(f32, f32, f32)> = cube.shared_vertex_iter().collect();
+    assert_eq!(vertices, expected);
+    let expected: Vec<Polygon<uint>> = cube.indexed_polygon_iter().map(|q| PolyQuad(q)).collect();
+    assert_eq!(polygons, expected);
+
+    // texture and normal indices are skipped, negative indices are
+    // relative and a pentagon becomes a fan
+    let obj = "# a comment\n\
+               v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv -1 0.5 0\n\
+               vt 0 0\nvn 0 0 1\n\
+               f 1/1/1 2/1/1 3/1/1\n\
+               f 1//1 3//1 -2//1\n\
+               f 1 2 3 4 5\n";
+    let mut r = MemReader::new(obj.as_bytes().to_vec());
+    let (vertices, polygons) = from_obj(&mut r).unwrap();
+    assert_eq!(vertices.len(), 5);
+    assert_eq!(polygons, vec![PolyTri(Triangle::new(0u, 1, 2)),
+                              PolyTri(Triangle::new(0u, 2, 3)),
+                              PolyTri(Triangle::new(0u, 1, 2)),
+                              PolyTri(Triangle::new(0u, 2, 3)),
+                              PolyTri(Triangle::new(0u, 3, 4))]);
+
+    // malformed faces are errors
+    for &bad in ["v 0 0 0\nf 1 1\n", "v 0 0 0\nf 1 2 3\n", "f a b c\n", "v 0 0\n"].iter() {
+        let mut r = MemReader::new(bad.as_bytes().to_vec());
+        assert!(from_obj(&mut r).is_err());
+    }
+}
+
+#[test]
+fn test_build_buffers() {
+    let sphere = SphereUV::new(16, 8);
+    let (vertices, indices) = build_buffers(&sphere);
+    assert_eq!(vertices.len(), sphere.shared_vertex_count());
+
+    // the two pole rows are triangles and the rest are quads
+    let triangles = 2 * 16 + 2 * 16 * (8 - 2);
+    assert_eq!(indices.len(), 3 * triangles);
+    for &i in indices.iter() {
+        assert!(i < vertices.len());
+    }
+
+    let expected: Vec<Triangle<(f32, f32, f32)>> = sphere.indexed_polygon_iter()
+        .triangulate()
+        .vertex(|i| sphere.shared_vertex(i))
+        .collect();
+    for (t, c) in expected.iter().zip(indices.as_slice().chunks(3)) {
+        assert_eq!(*t, Triangle::new(vertices[c[0]], vertices[c[1]], vertices[c[2]]));
+    }
+}
+
+#[test]
+fn test_split_non_planar() {
+    let flat = Quad::new((0., 0., 0.), (1., 0., 0.), (1., 1., 0.), (0., 1., 0.));
+    let warped = Quad::new((0., 0., 0.), (1., 0., 0.), (1., 1., 0.), (0., 1., 0.5));
+    assert!(flat.is_planar(1e-6));
+    assert!(!warped.is_planar(1e-6));
+    assert!(warped.is_planar(0.5));
+
+    let output: Vec<Polygon<(f32, f32, f32)>> = vec![flat, warped].into_iter()
+        .split_non_planar(1e-6)
+        .collect();
+    let (a, b) = warped.triangulate();
+    assert_eq!(output, vec![PolyQuad(flat), PolyTri(a), PolyTri(b)]);
+
+    // every quad of a torus is flat
+    assert_eq!(Torus::new(1., 0.25, 8, 6).split_non_planar(1e-5).count(), 8 * 6);
+}
+
+#[test]
+fn test_color_by_normal() {
+    let colored: Vec<Polygon<((f32, f32, f32), (f32, f32, f32))>> = Cube::new()
+        .map(|q| q.with_face_normal())
+        .color_by_normal()
+        .collect();
+
+    // the face pointing along +x is red
+    let mut found = false;
+    for q in colored.iter() {
+        let (p, c) = q.into_vertices().next().unwrap();
+        let (x, _, _) = p;
+        if c == (1., 0.5, 0.5) {
+            assert_eq!(x, 1.);
+            q.emit_vertices_ref(|&(_, v)| assert_eq!(v, c));
+            found = true;
+        }
+    }
+    assert!(found);
+}
+
+#[deriving(PartialEq, Show)]
+struct Vertex {
+    pos: [f32, ..3],
+    uv: [f32, ..2]
+}
+
+#[test]
+fn test_build_buffers_with() {
+    let cube = Cube::new().with_tex_coords();
+    let (vertices, indices) = build_buffers_with(&cube, |((x, y, z), (u, v))| {
+        Vertex { pos: [x, y, z], uv: [u, v] }
+    });
+    assert_eq!(vertices.len(), 24);
+    assert_eq!(indices.len(), 36);
+
+    for (i, v) in vertices.iter().enumerate() {
+        let ((x, y, z), (u, t)) = cube.shared_vertex(i);
+        assert_eq!(*v, Vertex { pos: [x, y, z], uv: [u, t] });
+    }
+}
+
+#[test]
+fn test_rotate() {
+    let input = vec![Triangle::new((1f32, 0f32, 0f32), (0., 1., 0.), (0., 0., 1.))];
+    let close = |(ax, ay, az): (f32, f32, f32), (bx, by, bz): (f32, f32, f32)| {
+        (ax - bx).abs() < 1e-6 && (ay - by).abs() < 1e-6 && (az - bz).abs() < 1e-6
+    };
+
+    // a quarter turn around +z takes +x to +y, the axis is normalized
+    let output: Vec<Triangle<(f32, f32, f32)>> = input.clone().into_iter()
+        .rotate((0., 0., 2.), PI_2 / 4.)
+        .collect();
+    assert!(close(output[0].x, (0., 1., 0.)));
+    assert!(close(output[0].y, (-1., 0., 0.)));
+    assert!(close(output[0].z, (0., 0., 1.)));
+
+    // a third of a turn around the diagonal cycles the axes
+    let output: Vec<Triangle<(f32, f32, f32)>> = input.clone().into_iter()
+        .rotate((1., 1., 1.), PI_2 / 3.)
+        .collect();
+    assert!(close(output[0].x, (0., 1., 0.)));
+    assert!(close(output[0].y, (0., 0., 1.)));
+
+    // a zero axis does nothing
+    let output: Vec<Triangle<(f32, f32, f32)>> = input.clone().into_iter()
+        .rotate((0., 0., 0.), 1.)
+        .collect();
+    assert_eq!(output, input);
+}
+
+#[test]
+fn test_instances() {
+    let base = vec![PolyTri(Triangle::new((0., 0., 0.), (1., 0., 0.), (0., 1., 0.))),
+                    PolyTri(Triangle::new((0., 0., 0.), (0., 1., 0.), (-1., 0., 0.)))];
+    let offsets = vec![(0., 0., 0.), (10., 0., 0.), (0., 0., 5.)];
+    let centroids: Vec<(f32, f32, f32)> = base.iter().map(|p| p.centroid()).collect();
+
+    let instances = Instances::new(base, offsets.clone().into_iter());
+    assert_eq!(instances.size_hint(), (6, Some(6)));
+
+    let output: Vec<Polygon<(f32, f32, f32)>> = instances.collect();
+    assert_eq!(output.len(), 6);
+    for (i, p) in output.iter().enumerate() {
+        let (cx, cy, cz) = centroids[i % 2];
+        let (ox, oy, oz) = offsets[i / 2];
+        let (x, y, z) = p.centroid();
+        assert!((x - (cx + ox)).abs() < 1e-6);
+        assert!((y - (cy + oy)).abs() < 1e-6);
+        assert!((z - (cz + oz)).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_barycentric() {
+    let tagged: Vec<Triangle<(uint, (f32, f32, f32))>> = vec![Quad::new(0u, 1, 2, 3)]
+        .into_iter()
+        .triangulate()
+        .barycentric()
+        .collect();
+    assert_eq!(tagged, vec![Triangle::new((0u, (1., 0., 0.)), (1, (0., 1., 0.)), (2, (0., 0., 1.))),
+                            Triangle::new((0u, (1., 0., 0.)), (2, (0., 1., 0.)), (3, (0., 0., 1.)))]);
+}
+
+#[test]
+fn test_topology() {
+    let sphere = SphereUV::new(12, 8);
+    let vertices: Vec<(f32, f32, f32)> = sphere.shared_vertex_iter().collect();
+    let polygons: Vec<Polygon<uint>> = sphere.indexed_polygon_iter().collect();
+    let t = topology(vertices.as_slice(), polygons.as_slice());
+    assert_eq!(t.vertices, 2 + 12 * 7);
+    assert_eq!(t.faces, 12 * 8);
+    assert_eq!(t.euler_characteristic, 2);
+    assert!(t.closed_manifold);
+
+    // the collapsed pole edges are not counted
+    let sphere = sphere.pole_style(PoleStyle::Collapsed);
+    let polygons: Vec<Polygon<uint>> = sphere.indexed_polygon_iter().collect();
+    let t = topology(vertices.as_slice(), polygons.as_slice());
+    assert_eq!(t.euler_characteristic, 2);
+    assert!(t.closed_manifold);
+
+    let plane = Plane::subdivide(3, 2);
+    let vertices: Vec<(f32, f32, f32)> = plane.shared_vertex_iter().collect();
+    let polygons: Vec<Polygon<uint>> = plane.indexed_polygon_iter().map(|q| PolyQuad(q)).collect();
+    let t = topology(vertices.as_slice(), polygons.as_slice());
+    assert_eq!((t.vertices, t.edges, t.faces), (12, 17, 6));
+    assert_eq!(t.euler_characteristic, 1);
+    assert!(!t.closed_manifold);
+}
+
+#[test]
+fn test_sphere_normals() {
+    let center = (1., -2., 0.5);
+    let sphere = SphereUV::at(12, 8, center, 3.);
+    let normals = sphere.with_normals();
+    assert_eq!(normals.shared_vertex_count(), sphere.shared_vertex_count());
+    assert_eq!(normals.indexed_polygon(5), sphere.indexed_polygon(5));
+
+    let mut count = 0u;
+    for p in normals {
+        p.emit_vertices(|(pos, n)| {
+            assert!((dot(n, n).sqrt() - 1.).abs() < 1e-6);
+            // the normal is the direction from the center
+            let (dx, dy, dz) = sub(pos, center);
+            let (nx, ny, nz) = n;
+            assert!((dx / 3. - nx).abs() < 1e-5);
+            assert!((dy / 3. - ny).abs() < 1e-5);
+            assert!((dz / 3. - nz).abs() < 1e-5);
+            count += 1;
+        });
+    }
+    assert_eq!(count, 4 * 12 * 6 + 3 * 12 * 2);
+}
+
+#[test]
+fn test_transform_uv() {
+    let input = vec![Triangle::new(((1f32, 2f32, 3f32), (0.5f32, 0.25f32)),
+                                   ((4., 5., 6.), (0., 0.)),
+                                   ((7., 8., 9.), (1., 1.)))];
+
+    let flipped: Vec<Triangle<((f32, f32, f32), (f32, f32))>> = input.clone().into_iter()
+        .flip_v()
+        .collect();
+    assert_eq!(flipped[0].x, ((1., 2., 3.), (0.5, 0.75)));
+    assert_eq!(flipped[0].y, ((4., 5., 6.), (0., 1.)));
+
+    let flipped: Vec<Triangle<((f32, f32, f32), (f32, f32))>> = input.clone().into_iter()
+        .flip_u()
+        .collect();
+    assert_eq!(flipped[0].x, ((1., 2., 3.), (0.5, 0.25)));
+    assert_eq!(flipped[0].z, ((7., 8., 9.), (0., 1.)));
+
+    let moved: Vec<Triangle<((f32, f32, f32), (f32, f32))>> = input.into_iter()
+        .scale_uv((2., 4.))
+        .offset_uv((1., -1.))
+        .collect();
+    assert_eq!(moved[0].x, ((1., 2., 3.), (2., 0.)));
+    assert_eq!(moved[0].z, ((7., 8., 9.), (3., 3.)));
+}
+
+#[test]
+fn test_rounded_box() {
+    // with no rounding this is the box itself
+    let rounded = RoundedBox::new((1., 2., 3.), 0., 4);
+    assert_eq!(rounded.size_hint(), (6 * 4 * 4, Some(6 * 4 * 4)));
+    let (min, max) = bounding_box(rounded.vertices()).unwrap();
+    assert_eq!(min, (-1., -2., -3.));
+    assert_eq!(max, (1., 2., 3.));
+    for i in range(0, rounded.shared_vertex_count()) {
+        let (x, y, z) = rounded.shared_vertex(i);
+        assert!(x.abs() == 1. || y.abs() == 2. || z.abs() == 3.);
+    }
+
+    // rounding a cube all the way gives a sphere
+    let rounded = RoundedBox::new((1., 1., 1.), 1., 8);
+    for i in range(0, rounded.shared_vertex_count()) {
+        let p = rounded.shared_vertex(i);
+        assert!((dot(p, p).sqrt() - 1.).abs() < 1e-5);
+        let n = rounded.shared_normal(i);
+        assert!(dot(n, p) > 0.99999);
+    }
+
+    // the faces point away from the center
+    for q in rounded {
+        match q {
+            PolyQuad(q) => {
+                let n = cross(sub(q.y, q.x), sub(q.w, q.x));
+                assert!(dot(n, q.x) > 0.);
+            }
+            _ => panic!("expected a quad")
+        }
+    }
+}
+
+#[test]
+fn test_group_by_material() {
+    let faces: Vec<Polygon<(uint, &str)>> = vec![
+        PolyQuad(Quad::new((0, "wood"), (1, "wood"), (2, "wood"), (3, "wood"))),
+        PolyTri(Triangle::new((4, "metal"), (5, "metal"), (6, "metal"))),
+        PolyTri(Triangle::new((7, "wood"), (8, "wood"), (9, "wood"))),
+        PolyQuad(Quad::new((10, "metal"), (11, "metal"), (12, "metal"), (13, "metal")))
+    ];
+
+    let groups = faces.into_iter().group_by_material();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups.get(&"wood"), Some(&vec![PolyQuad(Quad::new(0u, 1, 2, 3)),
+                                               PolyTri(Triangle::new(7u, 8, 9))]));
+    assert_eq!(groups.get(&"metal"), Some(&vec![PolyTri(Triangle::new(4u, 5, 6)),
+                                                PolyQuad(Quad::new(10u, 11, 12, 13))]));
+}
+
+#[test]
+fn test_plane_orientation() {
+    let plane = Plane::subdivide(3, 2).orientation(Orientation::XZ);
+    for i in range(0, plane.shared_vertex_count()) {
+        let (_, y, _) = plane.shared_vertex(i);
+        assert_eq!(y, 0.);
+    }
+    for i in range(0, plane.indexed_polygon_count()) {
+        let q = plane.indexed_polygon(i).map_vertex(|v| plane.shared_vertex(v));
+        let (nx, ny, nz) = cross(sub(q.y, q.x), sub(q.w, q.x));
+        assert!(nx == 0. && ny > 0. && nz == 0.);
+    }
+    for q in plane.map(quad) {
+        let (nx, ny, nz) = cross(sub(q.y, q.x), sub(q.w, q.x));
+        assert!(nx == 0. && ny > 0. && nz == 0.);
+    }
+}
+
+#[test]
+fn test_inspect_polygons() {
+    let mut seen = 0u;
+    let mut quads = 0u;
+    let polygons: Vec<Polygon<(f32, f32, f32)>> = Cube::new()
+        .inspect_polygons(|p| {
+            seen += 1;
+            match *p {
+                PolyQuad(_) => quads += 1,
+                PolyTri(_) => ()
+            }
+        })
+        .collect();
+
+    assert_eq!(seen, 6);
+    assert_eq!(quads, 6);
+    let expected: Vec<Polygon<(f32, f32, f32)>> = Cube::new().collect();
+    assert_eq!(polygons, expected);
+}
+
+#[test]
+fn test_sphere_centered_poles() {
+    let sphere = SphereUV::new(8, 4).with_tex_coords().centered_poles();
+
+    let mut top = Vec::new();
+    let mut bottom = Vec::new();
+    for (i, poly) in sphere.indexed_polygon_iter().enumerate() {
+        match poly {
+            PolyTri(Triangle{x, y, z}) => {
+                let (_, (apex, _)) = sphere.shared_vertex(x);
+                let (_, (a, _)) = sphere.shared_vertex(y);
+                let (_, (b, _)) = sphere.shared_vertex(z);
+                // the apex sits over the middle of the base
+                assert!((apex - (a + b) / 2.).abs() < 1e-6);
+                if i < 8 { top.push(apex) } else { bottom.push(apex) }
+            }
+            PolyQuad(_) => ()
+        }
+    }
+
+    for apexes in [top, bottom].iter() {
+        assert_eq!(apexes.len(), 8);
+        for (i, &u) in apexes.iter().enumerate() {
+            assert!((u - (i as f32 + 0.5) / 8.).abs() < 1e-6);
+        }
+    }
+}
+
+// rotate a triangle to start from its lowest index
+fn lowest_first(Triangle{x, y, z}: Triangle<uint>) -> (uint, uint, uint) {
+    if x < y && x < z { (x, y, z) } else if y < z { (y, z, x) } else { (z, x, y) }
+}
+
+// expand triangle strips into triangles, dropping the degenerate ones
+fn strip_triangles(strip: &[uint]) -> Vec<Triangle<uint>> {
+    let mut out = Vec::new();
+    for run in strip.split(|&i| i == PRIMITIVE_RESTART) {
+        for i in range(2, run.len()) {
+            let (a, b, c) = if i % 2 == 0 {
+                (run[i-2], run[i-1], run[i])
+            } else {
+                (run[i-1], run[i-2], run[i])
+            };
+            if a != b && b != c && c != a {
+                out.push(Triangle::new(a, b, c));
+            }
+        }
+    }
+    out
+}
+
+#[test]
+fn test_triangle_strip() {
+    let plane = Plane::subdivide(3, 2);
+    let strip = plane.triangle_strip();
+    assert_eq!(strip.iter().filter(|&&i| i == PRIMITIVE_RESTART).count(), 1);
+
+    let expected: Vec<Triangle<uint>> = plane.indexed_polygon_iter()
+        .triangulate()
+        .collect();
+    assert_eq!(strip_triangles(strip.as_slice()), expected);
+
+    // the sphere bands cover the same triangles, the poles only differ
+    // by which vertex their triangles start from
+    let sphere = SphereUV::new(6, 4);
+    let mut expected: Vec<(uint, uint, uint)> = sphere.indexed_polygon_iter()
+        .triangulate()
+        .map(|t| lowest_first(t))
+        .collect();
+    let mut found: Vec<(uint, uint, uint)> = strip_triangles(sphere.triangle_strip().as_slice())
+        .into_iter()
+        .map(|t| lowest_first(t))
+        .collect();
+    expected.sort();
+    found.sort();
+    assert_eq!(found, expected);
+}
+
+#[test]
+fn test_merge_coplanar() {
+    let plane = Plane::subdivide(4, 3);
+    let vertices: Vec<(f32, f32, f32)> = plane.shared_vertex_iter().collect();
+    let triangles: Vec<Triangle<uint>> = plane.indexed_polygon_iter()
+        .triangulate()
+        .collect();
+    assert_eq!(triangles.len(), 24);
+
+    let merged = merge_coplanar(vertices.as_slice(), triangles.as_slice(), 1e-5);
+    assert_eq!(merged.len(), 12);
+    let mut back: Vec<Triangle<uint>> = merged.into_iter().triangulate().collect();
+    let mut expected = triangles.clone();
+    back.sort_by(|a, b| (a.x, a.y, a.z).cmp(&(b.x, b.y, b.z)));
+    expected.sort_by(|a, b| (a.x, a.y, a.z).cmp(&(b.x, b.y, b.z)));
+    assert_eq!(back, expected);
+
+    // a fold along the diagonal keeps the two halves apart
+    let vertices = vec![(0f32, 0f32, 0f32), (1., 0., 0.), (1., 1., 1.), (0., 1., 0.)];
+    let triangles = vec![Triangle::new(0u, 1, 2), Triangle::new(0u, 2, 3)];
+    let merged = merge_coplanar(vertices.as_slice(), triangles.as_slice(), 1e-5);
+    assert_eq!(merged, vec![PolyTri(triangles[0]), PolyTri(triangles[1])]);
+}
+
+#[test]
+fn test_line_vertices() {
+    let lines = vec![Line::new((0f32, 0f32, 0f32), (1f32, 2f32, 3f32))];
+    let vertices: Vec<(f32, f32, f32)> = lines.into_iter()
+        .vertex(|(x, y, z)| (x * 2., y * 2., z * 2.))
+        .translate((1., 0., -1.))
+        .vertices()
+        .collect();
+    assert_eq!(vertices, vec![(1., 0., -1.), (3., 4., 5.)]);
+
+    let line = Line::new(1u, 2u);
+    assert_eq!(line.map_vertex_ref(|&v| v + 1), Line::new(2u, 3u));
+
+    // a stream can mix lines with polygons
+    let mixed = vec![PrimPolygon(PolyTri(Triangle::new(0u, 1, 2))),
+                     PrimLine(Line::new(2u, 3))];
+    let vertices: Vec<uint> = mixed.clone().into_iter()
+        .vertex(|v| v * 10)
+        .vertices()
+        .collect();
+    assert_eq!(vertices, vec![0, 10, 20, 20, 30]);
+    assert_eq!(mixed[1].map_vertex_ref(|&v| v + 1), PrimLine(Line::new(3u, 4)));
+}
+
+#[test]
+fn test_triangle_vertices() {
+    let triangles: Vec<Triangle<(f32, f32, f32)>> = SphereUV::new(8, 6)
+        .triangulate()
+        .collect();
+
+    let fast = triangles.clone().into_iter().triangle_vertices();
+    assert_eq!(fast.size_hint(), (triangles.len() * 3, Some(triangles.len() * 3)));
+
+    let fast: Vec<(f32, f32, f32)> = fast.collect();
+    let generic: Vec<(f32, f32, f32)> = triangles.into_iter().vertices().collect();
+    assert_eq!(fast, generic);
+}
+
+#[test]
+fn test_compact() {
+    let cube = Cube::new();
+    let vertices: Vec<(f32, f32, f32)> = cube.shared_vertex_iter().collect();
+    // keep the -x, -y and -z faces, none of which touch the corner at 1
+    let polygons: Vec<Polygon<uint>> = cube.indexed_polygon_iter()
+        .map(|q| PolyQuad(q))
+        .enumerate()
+        .filter(|&(i, _)| i % 2 == 0)
+        .map(|(_, p)| p)
+        .collect();
+
+    let (kept, compacted) = compact(vertices.as_slice(), polygons.as_slice());
+    assert_eq!(kept.len(), 7);
+    assert!(!kept.contains(&(1., 1., 1.)));
+    assert_eq!(compacted.len(), 3);
+
+    for (before, after) in polygons.into_iter().zip(compacted.into_iter()) {
+        let mut in_range = true;
+        let after = after.map_vertex(|i| {
+            in_range = in_range && i < kept.len();
+            kept[i]
+        });
+        assert!(in_range);
+        assert_eq!(before.map_vertex(|i| vertices[i]), after);
+    }
+}
+
+#[test]
+fn test_cylinder_uncapped() {
+    let cylinder = Cylinder::subdivide(8, 3).capped(false);
+    assert_eq!(cylinder.shared_vertex_count(), 8 * 4);
+    assert_eq!(cylinder.indexed_polygon_count(), 8 * 3);
+
+    for i in range(0, cylinder.shared_vertex_count()) {
+        let (x, y, _) = cylinder.shared_vertex(i);
+        // every vertex is on the wall, none are on the axis
+        assert!(((x * x + y * y).sqrt() - 1.).abs() < 1e-6);
+    }
+    for p in cylinder.indexed_polygon_iter() {
+        match p {
+            PolyQuad(q) => assert!(q.x < 32 && q.y < 32 && q.z < 32 && q.w < 32),
+            PolyTri(_) => panic!("an uncapped cylinder has no triangles")
+        }
+    }
+
+    let polygons: Vec<Polygon<(f32, f32, f32)>> = cylinder.collect();
+    assert_eq!(polygons.len(), 24);
+    let capped: Vec<Polygon<(f32, f32, f32)>> = Cylinder::subdivide(8, 3).collect();
+    assert_eq!(polygons.as_slice(), capped.slice(8, 32));
+}
+
+#[test]
+fn test_curvature() {
+    let sphere = IcoSphere::subdivide(3);
+    let vertices: Vec<(f32, f32, f32)> = sphere.shared_vertex_iter().collect();
+    let polygons: Vec<Polygon<uint>> = sphere.indexed_polygon_iter()
+        .map(|t| PolyTri(t))
+        .collect();
+
+    let (mean, gaussian) = curvature(vertices.as_slice(), polygons.as_slice());
+    assert_eq!(mean.len(), vertices.len());
+    for (h, k) in mean.iter().zip(gaussian.iter()) {
+        assert!((h.unwrap() - 1.).abs() < 0.01);
+        assert!((k.unwrap() - 1.).abs() < 0.01);
+    }
+
+    // a flat plane has no curvature and the edge has no estimate
+    let plane = Plane::subdivide(2, 2);
+    let vertices: Vec<(f32, f32, f32)> = plane.shared_vertex_iter().collect();
+    let polygons: Vec<Polygon<uint>> = plane.indexed_polygon_iter()
+        .map(|q| PolyQuad(q))
+        .collect();
+    let (mean, gaussian) = curvature(vertices.as_slice(), polygons.as_slice());
+    for v in range(0u, 9) {
+        if v == 4 {
+            assert!(mean[v].unwrap().abs() < 1e-6);
+            assert!(gaussian[v].unwrap().abs() < 1e-6);
+        } else {
+            assert_eq!(mean[v], None);
+            assert_eq!(gaussian[v], None);
+        }
+    }
+}
+
+#[test]
+fn test_fold_polygons() {
+    let count = Cube::new().fold_polygons(0u, |n, _| n + 1);
+    assert_eq!(count, 6);
+
+    // a cube with sides of length 1
+    let area = Cube::new().scale((0.5, 0.5, 0.5)).total_area();
+    assert!((area - 6.).abs() < 1e-5);
+
+    let ((x, y, z), r) = Cube::new().translate((1., 2., 3.)).bounding_sphere().unwrap();
+    assert_eq!((x, y, z), (1., 2., 3.));
+    assert!((r - 3f32.sqrt()).abs() < 1e-6);
+
+    let empty: Vec<Triangle<(f32, f32, f32)>> = Vec::new();
+    assert_eq!(empty.into_iter().bounding_sphere(), None);
+
+    // lines have no area but still have a bounding sphere
+    let lines = vec![Line::new((-1f32, 0f32, 0f32), (1f32, 0f32, 0f32))];
+    assert_eq!(lines.into_iter().bounding_sphere(), Some(((0., 0., 0.), 1.)));
+}
+
+#[test]
+fn test_mobius() {
+    let strip = Mobius::new(1., 0.4, 16);
+    assert_eq!(strip.shared_vertex_count(), 32);
+    assert_eq!(strip.indexed_polygon_count(), 16);
+
+    // the strip has one side, so the winding can not agree at the join
+    assert_eq!(check_winding(&strip), vec![0, 15]);
+
+    // the end of the strip lands on the start with its edges swapped
+    let last = strip.indexed_polygon(15);
+    let first = strip.indexed_polygon(0);
+    match (first, last) {
+        (PolyQuad(first), PolyQuad(last)) => {
+            assert_eq!((last.y, last.z), (first.w, first.x));
+        }
+        _ => panic!("expected quads")
+    }
+
+    let polygons: Vec<Polygon<(f32, f32, f32)>> = strip.collect();
+    assert_eq!(polygons.len(), 16);
+}
+
+#[test]
+fn test_polygon_from_vec() {
+    assert_eq!(Polygon::from_vec(vec![1u, 2, 3]), Some(PolyTri(Triangle::new(1u, 2, 3))));
+    assert_eq!(Polygon::from_vec(vec![1u, 2, 3, 4]), Some(PolyQuad(Quad::new(1u, 2, 3, 4))));
+    assert_eq!(Polygon::from_vec(vec![1u, 2, 3, 4, 5]), None);
+    assert_eq!(Polygon::from_vec(vec![1u, 2]), None);
+
+    let face = [4u, 5, 6];
+    assert_eq!(Polygon::from_slice(&face), Some(PolyTri(Triangle::new(4u, 5, 6))));
+    assert_eq!(Polygon::from_slice(face.slice_to(2)), None);
+}
+
+#[test]
+fn test_crease_normals() {
+    let cube = Cube::new();
+
+    // every edge of a cube is 90 degrees, so they all stay hard
+    let (vertices, quads) = generate_crease_normals(&cube, PI_2 / 12.);
+    assert_eq!(vertices.len(), 24);
+    assert_eq!(quads.len(), 6);
+    for &q in quads.iter() {
+        let face = q.map_vertex(|i| vertices[i]);
+        let n = face.map_vertex(|(p, _)| p).face_normal();
+        face.emit_vertices(|(_, vn)| assert_eq!(vn, n));
+    }
+
+    // a wide angle smooths every corner into a single vertex
+    let (vertices, quads) = generate_crease_normals(&cube, PI_2 / 3.);
+    assert_eq!(vertices.len(), 8);
+    assert_eq!(quads.len(), 6);
+    let smooth = generate_normals(&cube);
+    for &(p, n) in vertices.iter() {
+        let i = range(0, 8).find(|&i| cube.shared_vertex(i) == p).unwrap();
+        assert!(dot(sub(n, smooth[i]), sub(n, smooth[i])) < 1e-10);
+    }
+
+    // an angle of 0 keeps every face flat, even when the faces are not
+    // axis aligned and their normals are not bit identical
+    let sphere = IcoSphere::subdivide(1);
+    let (vertices, triangles) = generate_crease_normals(&sphere, 0.);
+    assert_eq!(triangles.len(), 80);
+    assert_eq!(vertices.len(), 80 * 3);
+    for &t in triangles.iter() {
+        let face = t.map_vertex(|i| vertices[i]);
+        let n = face.map_vertex(|(p, _)| p).face_normal();
+        face.emit_vertices(|(_, vn)| assert!(dot(sub(vn, n), sub(vn, n)) < 1e-10));
+    }
+}
+
+#[test]
+fn test_tagged() {
+    let tagged: Vec<Tagged<Polygon<(f32, f32, f32)>, uint>> = Cube::new()
+        .enumerate()
+        .map(|(i, q)| Tagged::new(q, i % 2))
+        .collect();
+
+    let mapped: Vec<Tagged<Polygon<(f32, f32, f32)>, uint>> = tagged.clone().into_iter()
+        .vertex(|(x, y, z)| (x * 2., y * 2., z * 2.))
+        .translate((0., 0., 1.))
+        .collect();
+
+    assert_eq!(mapped.len(), 6);
+    for (before, after) in tagged.iter().zip(mapped.iter()) {
+        assert_eq!(before.tag, after.tag);
+        let expected = before.polygon.map_vertex(|(x, y, z)| (x * 2., y * 2., z * 2. + 1.));
+        assert_eq!(after.polygon, expected);
+    }
+
+    let vertices: Vec<(f32, f32, f32)> = mapped.into_iter().vertices().collect();
+    assert_eq!(vertices.len(), 24);
+}